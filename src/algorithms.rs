@@ -1,10 +1,28 @@
+mod options;
+use options::likeliest;
+pub use options::{entropy, Frequency, HardMode, Options, Prior, Sigmoid, Strategy};
 mod naive;
 pub use naive::Naive;
 mod cached;
 pub use cached::Cached;
 mod mask_buckets;
 pub use mask_buckets::MaskBuckets;
+mod candidate;
+use candidate::Candidate;
+mod lookahead;
+use lookahead::Lookahead;
+mod memo;
+use memo::opener;
+pub use memo::Memo;
 mod memoized;
 pub use memoized::Memoized;
 mod interactive;
-pub use interactive::Interactive;
+pub use interactive::{Contradiction, Interactive, Suggestion};
+mod multi_board;
+pub use multi_board::MultiBoard;
+mod minimax;
+pub use minimax::Minimax;
+mod optimal;
+pub use optimal::Optimal;
+mod tree;
+pub use tree::TreeGuesser;
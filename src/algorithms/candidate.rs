@@ -0,0 +1,65 @@
+use crate::{Word, EPSILON};
+use std::cmp::Ordering;
+
+/// A guess being considered, with how good it is.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Candidate {
+    pub(crate) word: Word,
+    pub(crate) goodness: f64,
+    /// The probability of the guess being the answer: 0 if it has been ruled out.
+    pub(crate) probability: f64,
+}
+
+impl Candidate {
+    /// Whether this guess is better than `other`.
+    pub(crate) fn beats(&self, other: &Self) -> bool {
+        self.cmp(other).is_gt()
+    }
+
+    /// `Greater` if this guess is better than `other`.
+    ///
+    /// Ties (up to rounding errors) are pretty common when there are few words left.
+    /// They go to the more likely answer, then to the word first in alphabetical order,
+    /// so that the choice doesn't depend on the hashing.
+    ///
+    /// It isn't a total order, since ties don't chain: don't sort with it.
+    pub(crate) fn cmp(&self, other: &Self) -> Ordering {
+        if (self.goodness - other.goodness).abs() > EPSILON {
+            return self.goodness.total_cmp(&other.goodness);
+        }
+        self.probability
+            .total_cmp(&other.probability)
+            .then(other.word.cmp(&self.word))
+    }
+
+    /// Keeps the better of `best` and this guess.
+    pub(crate) fn keep_best(self, best: &mut Option<Self>) {
+        if best.is_none_or(|best| self.beats(&best)) {
+            *best = Some(self);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Candidate;
+    use crate::to_word;
+
+    #[test]
+    fn ties() {
+        let candidate = |word, goodness, probability| Candidate {
+            word: to_word(word),
+            goodness,
+            probability,
+        };
+        let best = candidate("fight", 2.0, 0.0);
+        assert!(candidate("right", 2.5, 0.0).beats(&best));
+        assert!(!candidate("right", 1.5, 0.5).beats(&best));
+        // Within rounding errors, the possible answer wins, whatever the order.
+        let likely = candidate("wight", 2.0 - 1e-15, 0.1);
+        assert!(likely.beats(&best) && !best.beats(&likely));
+        // Then the word first in alphabetical order.
+        let other = candidate("eight", 2.0, 0.0);
+        assert!(other.beats(&best) && !best.beats(&other));
+    }
+}
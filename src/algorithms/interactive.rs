@@ -1,183 +1,506 @@
-use crate::{nice_print, to_word, Correctness, Guess, Guesser, Word, DICTIONARY};
-use ascii::ToAsciiChar;
-use std::collections::HashMap;
-
-use ndarray::Array5;
+use super::{entropy, likeliest, Candidate, HardMode, Lookahead, Memo, Options};
+use crate::matrix::PatternMatrix;
+use crate::{nice_print, Correctness, Guess, Guesser, Pattern, Word};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
 
 pub struct Interactive {
-    initial: HashMap<Word, usize>,
-    remaining: HashMap<Word, usize>,
-    hard: bool,
+    /// The prior weight of every word of the dictionary, even removed ones.
+    weights: HashMap<Word, f64>,
+    /// Given to words that are not in the dictionary: they must be rare.
+    unknown_weight: f64,
+    initial: HashMap<Word, f64>,
+    remaining: HashMap<Word, f64>,
+    /// Number of guesses of the history that `remaining` takes into account.
+    observed: usize,
+    options: Options,
+    memo: Arc<Mutex<Memo>>,
+    /// Whether the word lists and options are still the ones the memo was built for.
     use_memo: bool,
+    /// Number of guesses ranked along with the best one.
+    list_size: usize,
+    /// The best guesses of the last call to `guess`, best first.
+    ranking: Vec<Suggestion>,
+}
+
+/// A guess of the ranking, with what it is expected to do.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Suggestion {
+    pub word: Word,
+    /// Expected information of its pattern, in bits.
+    pub information: f64,
+    /// Expected number of words still possible after it.
+    pub expected_remaining: f64,
+    /// Probability that it is the answer.
+    pub probability: f64,
+    /// Whether it could still be the answer.
+    pub possible: bool,
 }
 
 impl Interactive {
     pub fn new() -> Self {
-        let initial = HashMap::from_iter(DICTIONARY.lines().map(|line| {
-            let (word, count) = line
-                .split_once(' ')
-                .expect("Every line must be of the format: word + space + frequency");
-            let word: [char; 5] = word.chars().collect::<Vec<char>>().try_into().unwrap();
-            let count: usize = count.parse().expect("Every count should be a number");
-            (word.map(|c| c.to_ascii_char().unwrap()), count)
-        }));
+        Self::with_options(Options::default())
+    }
+
+    pub fn with_options(options: Options) -> Self {
+        let memo = Arc::new(Mutex::new(Memo::new(&options)));
+        Self::with_memo(options, memo)
+    }
+
+    /// Panics if the memo was built for other options.
+    pub fn with_memo(options: Options, memo: Arc<Mutex<Memo>>) -> Self {
+        assert!(
+            memo.lock().unwrap().is_for(&options),
+            "The memo was built for another configuration"
+        );
+        let weights = options.initial_weights();
+        let unknown_weight = weights.values().copied().fold(f64::INFINITY, f64::min);
+        let initial = weights.clone();
         let remaining = initial.clone();
         Self {
+            weights,
+            unknown_weight,
             initial,
             remaining,
-            hard: false,
+            observed: 0,
+            options,
+            memo,
             use_memo: true,
+            list_size: 0,
+            ranking: Vec::new(),
         }
     }
 
+    /// Keeps `guess` from printing whatever the options say, for full-screen interfaces.
+    pub fn set_quiet(&mut self, quiet: bool) {
+        self.options.verbose &= !quiet;
+    }
+
+    /// Ranks the `n` best guesses every time a guess is made, see `ranking`.
+    pub fn set_list_size(&mut self, n: usize) {
+        self.list_size = n;
+    }
+
+    /// The best guesses found by the last call to `guess`, as many as the list size
+    /// (fewer if not as many words are allowed): the guess it returned first, then the others
+    /// by their one-step goodness (with lookahead, it doesn't look further for the others).
+    pub fn ranking(&self) -> &[Suggestion] {
+        &self.ranking
+    }
+
     pub fn remove(&mut self, word: &Word) {
         self.initial.remove(word);
         self.remaining.remove(word);
         self.use_memo = false;
-        println!(
-            "Adjusted to the fact that {} is not allowed.",
-            nice_print(*word)
-        );
     }
 
     pub fn eliminate(&mut self, word: &Word) {
         self.remaining.remove(word);
         self.use_memo = false;
-        println!(
-            "Adjusted to the assumption that {} is not the answer.",
-            nice_print(*word)
-        );
+    }
+
+    fn weight(&self, word: &Word) -> f64 {
+        self.weights
+            .get(word)
+            .copied()
+            .unwrap_or(self.unknown_weight)
     }
 
     pub fn add(&mut self, word: &Word) {
         if !self.initial.contains_key(word) {
-            self.initial.insert(*word, 1);
+            self.initial.insert(*word, self.weight(word));
             self.use_memo = false;
         }
-        println!(
-            "Adjusted to the fact that {} is allowed.",
-            nice_print(*word)
-        );
     }
 
     pub fn consider(&mut self, word: &Word) {
-        if let Some(goodness) = self.initial.get(word) {
+        if let Some(&weight) = self.initial.get(word) {
             if !self.remaining.contains_key(word) {
-                self.remaining.insert(*word, *goodness);
+                self.remaining.insert(*word, weight);
                 self.use_memo = false;
             }
         } else {
-            self.initial.insert(*word, 1);
-            self.remaining.insert(*word, 1);
+            let weight = self.weight(word);
+            self.initial.insert(*word, weight);
+            self.remaining.insert(*word, weight);
             self.use_memo = false;
         }
-        println!(
-            "Adjusted to the assumption that {} might be the answer.",
-            nice_print(*word)
-        );
+    }
+
+    /// Number of words that could still be the answer, as of the last guess observed.
+    pub fn remaining_count(&self) -> usize {
+        self.remaining.len()
     }
 
     pub fn remaining(&self) {
-        for (&word, _) in &self.remaining {
+        for &word in self.remaining.keys() {
             print!("{} ", nice_print(word));
         }
     }
 
-    pub fn hard(&mut self) {
-        self.hard = true;
+    pub fn hard(&mut self, mode: HardMode) {
+        if mode != self.options.hard_mode {
+            self.options.hard_mode = mode;
+            self.use_memo = false;
+        }
+    }
+
+    /// Catches up with the guesses of `history` not observed yet, as `guess` does before guessing.
+    ///
+    /// The words eliminated or considered in between are kept: only a history shorter than
+    /// the one observed so far starts over from all the words, as the start of a new game.
+    pub fn observe(&mut self, history: &[Guess]) {
+        if history.len() < self.observed {
+            self.remaining = self.initial.clone();
+            self.observed = 0;
+        }
+        for guess in &history[self.observed..] {
+            PatternMatrix::global().retain_matching(&mut self.remaining, guess);
+        }
+        self.observed = history.len();
+    }
+
+    /// Checks that `guess`, to come after the observed `history`, leaves some word possible.
+    pub fn validate(&self, history: &[Guess], guess: &Guess) -> Result<(), Contradiction> {
+        if self.remaining.keys().any(|word| guess.matches(word)) {
+            return Ok(());
+        }
+        let words: Vec<Word> = self.initial.keys().copied().collect();
+        Err(Contradiction::find(&words, history, guess))
+    }
+
+    pub fn memo(&self) -> &Arc<Mutex<Memo>> {
+        &self.memo
+    }
+}
+
+impl Default for Interactive {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-struct Candidate {
-    word: Word,
-    goodness: f64,
+/// Why a guess leaves no word possible.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Contradiction {
+    /// The earlier guesses it contradicts, by index in the history.
+    pub conflicts: Vec<usize>,
+    /// The guesses that would leave some words possible if a single tile was changed:
+    /// their index in the history (its length for the new guess) and the corrected guess.
+    pub corrections: Vec<(usize, Guess)>,
+    /// Whether some words fit, but they were assumed not to be the answer.
+    pub eliminated: bool,
+}
+
+impl Contradiction {
+    fn find(words: &[Word], history: &[Guess], guess: &Guess) -> Self {
+        let fits = |guesses: &[Guess]| {
+            words
+                .iter()
+                .any(|word| guesses.iter().all(|g| g.matches(word)))
+        };
+        let mut all = history.to_vec();
+        all.push(*guess);
+        if fits(&all) {
+            return Self {
+                eliminated: true,
+                ..Self::default()
+            };
+        }
+
+        let mut conflicts = Vec::new();
+        if fits(&[*guess]) {
+            // The earlier guesses without which it would fit.
+            conflicts = (0..history.len())
+                .filter(|&i| {
+                    let mut others = all.clone();
+                    others.remove(i);
+                    fits(&others)
+                })
+                .collect();
+            if conflicts.is_empty() {
+                // More than one is to blame: the ones it contradicts on their own.
+                conflicts = (0..history.len())
+                    .filter(|&i| !fits(&[history[i], *guess]))
+                    .collect();
+            }
+        }
+
+        let mut corrections = Vec::new();
+        for i in 0..all.len() {
+            let tiles = all[i].mask.to_correctness();
+            for position in 0..5 {
+                for tile in [
+                    Correctness::Wrong,
+                    Correctness::Misplaced,
+                    Correctness::Correct,
+                ] {
+                    if tile == tiles[position] {
+                        continue;
+                    }
+                    let mut corrected = tiles;
+                    corrected[position] = tile;
+                    let mut guesses = all.clone();
+                    guesses[i].mask = corrected.into();
+                    if fits(&guesses) {
+                        corrections.push((i, guesses[i]));
+                    }
+                }
+            }
+        }
+
+        Self {
+            conflicts,
+            corrections,
+            eliminated: false,
+        }
+    }
 }
 
 impl Guesser for Interactive {
     fn guess(&mut self, history: &[Guess]) -> Word {
-        if let Some(last) = history.last() {
-            self.remaining.retain(|word, _| last.matches(word));
-
+        let matrix = PatternMatrix::global();
+        self.observe(history);
+        self.ranking.clear();
+        if !history.is_empty() {
             let num_remains = self.remaining.len();
-            println!("Number of remaining possibilities: {}", num_remains);
+            if self.options.verbose {
+                println!("Number of remaining possibilities: {}", num_remains);
+            }
             // If only 1 possibility remains, return that as the guess.
             // This is essential, because otherwise,
             // any guess would be considered to be as good as any other.
-            if num_remains == 1 {
-                return *self.remaining.iter().next().unwrap().0;
+            if num_remains == 0 {
+                // The history contradicts itself (see `validate`): any word will do.
+                return likeliest(&self.initial).unwrap_or(history[0].word);
             }
-        } else {
-            // First guess
-            self.remaining = self.initial.clone();
-            if self.use_memo {
-                return to_word("tares");
+            if num_remains == 1 {
+                let word = *self.remaining.iter().next().unwrap().0;
+                if self.list_size > 0 {
+                    self.ranking.push(Suggestion {
+                        word,
+                        information: 0.0,
+                        expected_remaining: 1.0,
+                        probability: 1.0,
+                        possible: true,
+                    });
+                }
+                return word;
             }
         }
 
-        if self.hard {
-            self.initial = self.remaining.clone();
+        // The ranking is made while looking for the best guess, which the memo would skip.
+        if self.use_memo && self.list_size == 0 {
+            if let Some(word) = self.memo.lock().unwrap().get(history) {
+                return word;
+            }
         }
 
-        let remaining_count: usize = self.remaining.iter().map(|(_, &c)| c).sum();
+        // Words added by the user are not in the matrix,
+        // so their patterns have to be computed on the fly.
+        // Sorted, for the sums to come out the same every time.
+        let mut candidates: Vec<(Word, Option<usize>, f64)> = self
+            .remaining
+            .iter()
+            .map(|(&word, &count)| (word, matrix.index_of(&word), count))
+            .collect();
+        candidates.sort_unstable_by_key(|&(word, _, _)| word);
+        let mut guesses: Vec<Word> = self.initial.keys().copied().collect();
+        guesses.sort_unstable();
 
+        let remaining_count: f64 = candidates.iter().map(|(_, _, count)| count).sum();
+        let remaining_entropy = entropy(
+            candidates.iter().map(|(_, _, count)| count),
+            remaining_count,
+        );
+
+        let mut lookahead = Lookahead::new(&self.options);
+        let mut ranked: Vec<(Candidate, Suggestion)> = Vec::new();
         let mut best: Option<Candidate> = None;
-        for (&word, _) in &self.initial {
+        for word in guesses {
+            if !self
+                .options
+                .hard_mode
+                .allows(history, &word, self.remaining.contains_key(&word))
+            {
+                continue;
+            }
             // measure goodness, which is the expected value of the information
             // - SUM_i p_i * log_2(p_i)
 
-            let mut mask_buckets = Array5::<usize>::zeros((3, 3, 3, 3, 3));
-            for (candidate, count) in &self.remaining {
-                let mask = Correctness::compute(candidate, &word);
-                mask_buckets[[
-                    mask[0] as usize,
-                    mask[1] as usize,
-                    mask[2] as usize,
-                    mask[3] as usize,
-                    mask[4] as usize,
-                ]] += count;
+            let row = matrix.index_of(&word).map(|g| matrix.row(g));
+            let mut mask_buckets = [0.0; Pattern::COUNT];
+            let mut sizes = [0usize; Pattern::COUNT];
+            for &(candidate, idx, count) in &candidates {
+                let pattern = match (row, idx) {
+                    (Some(row), Some(idx)) => row[idx],
+                    _ => Pattern::compute(&candidate, &word).id(),
+                };
+                mask_buckets[pattern as usize] += count;
+                sizes[pattern as usize] += 1;
             }
 
             let mut goodness = 0.0;
-            for mask in Correctness::all_patterns() {
-                let in_pattern_total = mask_buckets[[
-                    mask[0] as usize,
-                    mask[1] as usize,
-                    mask[2] as usize,
-                    mask[3] as usize,
-                    mask[4] as usize,
-                ]];
-                if in_pattern_total == 0 {
+            for in_pattern_total in mask_buckets {
+                if in_pattern_total <= 0.0 {
                     // avoid indeterminate arithmetic (NaN) which should evaluate to 0
                     continue;
                 }
-                let prob_of_pattern = (in_pattern_total as f64) / (remaining_count as f64);
+                let prob_of_pattern = in_pattern_total / remaining_count;
                 goodness -= prob_of_pattern * prob_of_pattern.log2();
             }
-
-            if let Some(c) = best {
-                use crate::EPSILON;
-
-                // Is this one better?
-                if goodness > c.goodness + EPSILON {
-                    best = Some(Candidate { word, goodness });
-
-                // Tie is pretty common when there are few words left.
-                // Make sure to handle this situation well.
-                } else if !(c.goodness > goodness + EPSILON) {
-                    // disfavor a word that has been ruled out
-                    // if neither word has been ruled out, favor the more common one
-                    if !self.remaining.contains_key(&c.word)
-                        || ((self.remaining.contains_key(&word))
-                            && (self.initial.get(&word) > self.initial.get(&c.word)))
-                    {
-                        best = Some(Candidate { word, goodness });
-                    }
-                }
-            } else {
-                best = Some(Candidate { word, goodness });
+            let information = goodness;
+            let probability = self
+                .remaining
+                .get(&word)
+                .map_or(0.0, |w| w / remaining_count);
+            // turn it into whatever the strategy optimizes
+            let goodness =
+                self.options
+                    .strategy
+                    .goodness(information, remaining_entropy, probability);
+            let candidate = Candidate {
+                word,
+                goodness,
+                probability,
+            };
+            if self.list_size > 0 {
+                let expected_remaining = mask_buckets
+                    .iter()
+                    .zip(sizes)
+                    .map(|(in_pattern_total, size)| {
+                        in_pattern_total / remaining_count * size as f64
+                    })
+                    .sum();
+                ranked.push((
+                    candidate,
+                    Suggestion {
+                        word,
+                        information,
+                        expected_remaining,
+                        probability,
+                        possible: self.remaining.contains_key(&word),
+                    },
+                ));
+            }
+            if let Some(lookahead) = &mut lookahead {
+                lookahead.push(word, (word, row), goodness);
             }
+            candidate.keep_best(&mut best);
+        }
+        let mut guess = best.unwrap().word;
+        if let Some(lookahead) = lookahead {
+            let weights: Vec<f64> = candidates.iter().map(|&(_, _, count)| count).collect();
+            guess = lookahead.choose(guess, &weights, |&(word, row), c| {
+                let (candidate, idx, _) = candidates[c];
+                match (row, idx) {
+                    (Some(row), Some(idx)) => row[idx],
+                    _ => Pattern::compute(&candidate, &word).id(),
+                }
+            });
+        }
+        self.ranking = rank(ranked, guess, self.list_size);
+        if self.use_memo {
+            self.memo.lock().unwrap().insert(history, guess);
+        }
+        guess
+    }
+}
+
+/// The `n` best of the `ranked` guesses: `guess` first, since the lookahead
+/// may have chosen it further down, then compared as for the choice of the guess.
+fn rank(mut ranked: Vec<(Candidate, Suggestion)>, guess: Word, n: usize) -> Vec<Suggestion> {
+    // One at a time, in the order they were compared in:
+    // the comparison isn't a total order to sort with.
+    let mut best = Vec::with_capacity(n.min(ranked.len()));
+    while best.len() < n {
+        let Some(i) = (0..ranked.len()).max_by(|&i, &j| {
+            let (a, b) = (&ranked[i].0, &ranked[j].0);
+            (a.word == guess)
+                .cmp(&(b.word == guess))
+                .then_with(|| a.cmp(b))
+        }) else {
+            break;
+        };
+        best.push(ranked.remove(i).1);
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{rank, Candidate, Contradiction, Suggestion};
+    use crate::{to_word, Guess, Word};
+
+    fn guess(word: &str, mask: &str) -> Guess {
+        Guess {
+            word: to_word(word),
+            mask: mask.parse().unwrap(),
         }
-        best.unwrap().word
+    }
+
+    #[test]
+    fn contradiction() {
+        let words: Vec<Word> = ["right", "fight", "sight", "cigar"].map(to_word).into();
+        let history = [guess("tares", "+----"), guess("light", "-####")];
+
+        // No word starts with an "l": the guess is wrong on its own.
+        let c = Contradiction::find(&words, &history, &guess("lumpy", "#----"));
+        assert!(c.conflicts.is_empty());
+        assert!(c.corrections.contains(&(2, guess("lumpy", "-----"))));
+        assert!(!c.eliminated);
+
+        // Both earlier guesses rule "cigar" out.
+        let c = Contradiction::find(&words, &history, &guess("cigar", "#####"));
+        assert_eq!(c.conflicts, [0, 1]);
+        assert!(c.corrections.is_empty());
+
+        // Typing "s" as gray in the first guess is fixed by making it yellow.
+        let c = Contradiction::find(&words, &history, &guess("sight", "#####"));
+        assert_eq!(c.conflicts, [0]);
+        assert!(c.corrections.contains(&(0, guess("tares", "+---+"))));
+        assert!(c.corrections.iter().all(|&(i, _)| i == 0 || i == 2));
+
+        // Some words still fit.
+        let c = Contradiction::find(&words, &history, &guess("fight", "#####"));
+        assert!(c.eliminated);
+    }
+
+    #[test]
+    fn ranking() {
+        let suggestion = |word, goodness, probability| {
+            let candidate = Candidate {
+                word: to_word(word),
+                goodness,
+                probability,
+            };
+            let suggestion = Suggestion {
+                word: to_word(word),
+                information: 0.0,
+                expected_remaining: 0.0,
+                probability,
+                possible: probability > 0.0,
+            };
+            (candidate, suggestion)
+        };
+        let ranked = vec![
+            suggestion("fight", 1.0, 0.0),
+            suggestion("light", 2.0 + 1e-15, 0.0),
+            suggestion("eight", 2.0, 0.0),
+            suggestion("right", 2.0, 0.5),
+            suggestion("might", 3.0, 0.0),
+        ];
+        // As if the lookahead had chosen "fight". Then the possible answer wins the tie,
+        // and the rounding errors don't count.
+        let words: Vec<Word> = rank(ranked, to_word("fight"), 5)
+            .into_iter()
+            .map(|s| s.word)
+            .collect();
+        assert_eq!(
+            words,
+            ["fight", "might", "right", "eight", "light"].map(to_word)
+        );
     }
 }
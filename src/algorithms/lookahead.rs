@@ -0,0 +1,149 @@
+use super::{entropy, Options};
+use crate::{Pattern, Word, EPSILON};
+
+/// Collects the one-step goodness of every allowed guess,
+/// to then look one guess further ahead for the best ones.
+///
+/// `K` is whatever the solver looks up the patterns of a guess with.
+pub(crate) struct Lookahead<K> {
+    top: usize,
+    guesses: Vec<(Word, K, f64)>,
+}
+
+impl<K> Lookahead<K> {
+    /// `None` unless the options ask for it.
+    pub(crate) fn new(options: &Options) -> Option<Self> {
+        options.lookahead.map(|top| Self {
+            top: top.max(1),
+            guesses: Vec::new(),
+        })
+    }
+
+    pub(crate) fn push(&mut self, word: Word, key: K, goodness: f64) {
+        self.guesses.push((word, key, goodness));
+    }
+
+    /// Out of `best` (the best guess by one-step goodness) and the next best ones,
+    /// the one with the most two-step information.
+    ///
+    /// `weights[c]` is the weight of candidate `c`, and `pattern(key, c)`
+    /// the pattern id the guess of `key` receives if `c` is the answer.
+    pub(crate) fn choose(
+        mut self,
+        best: Word,
+        weights: &[f64],
+        pattern: impl Fn(&K, usize) -> u8,
+    ) -> Word {
+        // Sorted by word for ties, so that the choice doesn't depend on the hashing.
+        let mut order: Vec<usize> = (0..self.guesses.len()).collect();
+        order.sort_by(|&a, &b| {
+            let (a, b) = (&self.guesses[a], &self.guesses[b]);
+            (b.0 == best)
+                .cmp(&(a.0 == best))
+                .then(b.2.total_cmp(&a.2))
+                .then(a.0.cmp(&b.0))
+        });
+        order.truncate(self.top);
+
+        let mut chosen = best;
+        let mut most = f64::NEG_INFINITY;
+        for first in order {
+            let information =
+                two_step_information(&self.guesses[first].1, &self.guesses, weights, &pattern);
+            if information > most + EPSILON {
+                chosen = self.guesses[first].0;
+                most = information;
+            }
+        }
+        self.guesses.clear();
+        chosen
+    }
+}
+
+/// The expected information of guessing `first`,
+/// and then the best of `guesses` for the pattern received.
+fn two_step_information<K>(
+    first: &K,
+    guesses: &[(Word, K, f64)],
+    weights: &[f64],
+    pattern: &impl Fn(&K, usize) -> u8,
+) -> f64 {
+    let total: f64 = weights.iter().sum();
+    let mut buckets: Vec<Vec<usize>> = vec![Vec::new(); Pattern::COUNT];
+    for c in 0..weights.len() {
+        buckets[pattern(first, c) as usize].push(c);
+    }
+
+    let mut information = 0.0;
+    let mut sums = [0.0; Pattern::COUNT];
+    let mut touched = Vec::new();
+    for (id, bucket) in buckets.iter().enumerate() {
+        let in_bucket: f64 = bucket.iter().map(|&c| weights[c]).sum();
+        if in_bucket <= 0.0 {
+            // avoid indeterminate arithmetic (NaN) which should evaluate to 0
+            continue;
+        }
+        let prob_of_pattern = in_bucket / total;
+        information -= prob_of_pattern * prob_of_pattern.log2();
+        if id == Pattern::SOLVED.index() || bucket.len() == 1 {
+            // Nothing left to learn.
+            continue;
+        }
+
+        // No follow-up can tell more than which one of them is the answer.
+        let ceiling = entropy(bucket.iter().map(|&c| &weights[c]), in_bucket);
+        let mut best: f64 = 0.0;
+        for (_, key, _) in guesses {
+            for &c in bucket {
+                let id = pattern(key, c) as usize;
+                if sums[id] == 0.0 {
+                    touched.push(id);
+                }
+                sums[id] += weights[c];
+            }
+            let mut follow_up = 0.0;
+            for id in touched.drain(..) {
+                let prob = sums[id] / in_bucket;
+                if prob > 0.0 {
+                    follow_up -= prob * prob.log2();
+                }
+                sums[id] = 0.0;
+            }
+            best = best.max(follow_up);
+            if best >= ceiling - EPSILON {
+                break;
+            }
+        }
+        information += prob_of_pattern * best;
+    }
+    information
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Lookahead;
+    use crate::algorithms::Options;
+    use crate::{to_word, Pattern, Word};
+
+    #[test]
+    fn sees_the_trap() {
+        // Guessing one of the ?ight words only rules that one out,
+        // while "fumed" followed by "waltz" tells most of them apart.
+        let candidates = ["fight", "might", "light", "sight", "tight", "night"].map(to_word);
+        let weights = [1.0; 6];
+        let guesses: Vec<Word> = ["fight", "fumed", "waltz", "snort"].map(to_word).into();
+        let options = Options {
+            lookahead: Some(guesses.len()),
+            ..Options::default()
+        };
+        let mut lookahead = Lookahead::new(&options).unwrap();
+        for &guess in &guesses {
+            lookahead.push(guess, guess, 0.0);
+        }
+        let pattern = |guess: &Word, c: usize| Pattern::compute(&candidates[c], guess).id();
+        let chosen = lookahead.choose(to_word("fight"), &weights, pattern);
+        assert_ne!(chosen, to_word("fight"));
+
+        assert!(Lookahead::<Word>::new(&Options::default()).is_none());
+    }
+}
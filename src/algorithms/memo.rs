@@ -0,0 +1,199 @@
+use super::Options;
+use crate::{words::WordLists, Guess, Word};
+use once_cell::sync::Lazy;
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::Path,
+    sync::Mutex,
+};
+
+const HEADER: &str = "# rogerthat memo v1";
+
+/// The guess chosen after every history encountered so far.
+///
+/// A guess only depends on the history and the solver configuration,
+/// so the memo records the configuration it was built for,
+/// and refuses to be loaded for a different one.
+#[derive(Debug, Clone)]
+pub struct Memo {
+    config: String,
+    table: HashMap<Vec<Guess>, Word>,
+}
+
+impl Memo {
+    pub fn new(options: &Options) -> Self {
+        Self {
+            config: fingerprint(options),
+            table: HashMap::new(),
+        }
+    }
+
+    pub fn get(&self, history: &[Guess]) -> Option<Word> {
+        self.table.get(history).copied()
+    }
+
+    pub fn insert(&mut self, history: &[Guess], guess: Word) {
+        self.table.insert(history.to_vec(), guess);
+    }
+
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
+    /// Whether the memo was built for these options (and the current dictionary).
+    pub fn is_for(&self, options: &Options) -> bool {
+        self.config == fingerprint(options)
+    }
+
+    /// Writes one line per history: every guess with its pattern, then `=>` and the next guess.
+    /// For instance: `tares -+--# clout --#-- => bumph`.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        writeln!(out, "{}", HEADER)?;
+        writeln!(out, "config {}", self.config)?;
+        // Sorted, so that the file is stable and diffable.
+        let mut entries: Vec<_> = self.table.iter().collect();
+        entries.sort_by_key(|(history, _)| {
+            history.iter().map(|g| (g.word, g.mask)).collect::<Vec<_>>()
+        });
+        for (history, guess) in entries {
+            for g in history {
+                write!(out, "{} {} ", g.word, g.mask.to_text())?;
+            }
+            writeln!(out, "=> {}", guess)?;
+        }
+        out.flush()
+    }
+
+    /// Reads a file written by `save`, failing if it was built for other options.
+    pub fn load(path: &Path, options: &Options) -> io::Result<Self> {
+        let invalid = |line: usize, msg: String| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}:{}: {}", path.display(), line, msg),
+            )
+        };
+
+        let mut lines = BufReader::new(File::open(path)?).lines();
+        if lines.next().transpose()?.as_deref() != Some(HEADER) {
+            return Err(invalid(1, "not a memo file".to_string()));
+        }
+        let config = lines.next().transpose()?.unwrap_or_default();
+        let mut memo = Self::new(options);
+        if config.strip_prefix("config ") != Some(memo.config.as_str()) {
+            return Err(invalid(
+                2,
+                format!(
+                    "memo was built for another configuration: {}",
+                    config.trim_start_matches("config ")
+                ),
+            ));
+        }
+
+        for (i, line) in lines.enumerate() {
+            let line = line?;
+            let (history, guess) = line
+                .split_once("=>")
+                .ok_or_else(|| invalid(i + 3, "expected '=>'".to_string()))?;
+            let parse_word = |s: &str| {
+                s.parse::<Word>()
+                    .map_err(|e| invalid(i + 3, format!("invalid word {:?}: {}", s, e)))
+            };
+            let guess = parse_word(guess.trim())?;
+            let tokens: Vec<&str> = history.split_whitespace().collect();
+            if !tokens.len().is_multiple_of(2) {
+                return Err(invalid(i + 3, "expected word + pattern pairs".to_string()));
+            }
+            let history = tokens
+                .chunks(2)
+                .map(|pair| {
+                    Ok(Guess {
+                        word: parse_word(pair[0])?,
+                        mask: pair[1].parse().map_err(|e| {
+                            invalid(i + 3, format!("invalid pattern {:?}: {}", pair[1], e))
+                        })?,
+                    })
+                })
+                .collect::<io::Result<Vec<_>>>()?;
+            memo.table.insert(history, guess);
+        }
+        Ok(memo)
+    }
+}
+
+/// The first guess of every configuration played so far.
+static OPENERS: Lazy<Mutex<HashMap<String, Word>>> = Lazy::new(Default::default);
+
+/// The first guess under `options`. It takes about as long to find as the rest of the game,
+/// and only depends on the options, so `compute` is only called for the first game.
+/// Solvers that score guesses their own way tell their openers apart with `variant`.
+pub(crate) fn opener(options: &Options, variant: &str, compute: impl FnOnce() -> Word) -> Word {
+    let key = format!("{} {}", fingerprint(options), variant);
+    // Stay locked while computing: the games played in parallel would only compute it again.
+    let mut openers = OPENERS.lock().unwrap();
+    *openers.entry(key).or_insert_with(compute)
+}
+
+/// Everything a guess depends on besides the history.
+fn fingerprint(options: &Options) -> String {
+    // FNV-1a, because the std hashers are not guaranteed to be stable.
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for (word, count) in &WordLists::global().dictionary {
+        for byte in word.as_bytes().iter().chain(&count.to_le_bytes()) {
+            hash = (hash ^ *byte as u64).wrapping_mul(0x100000001b3);
+        }
+    }
+    format!(
+        "hard_mode={:?} prior={:?} strategy={:?} lookahead={:?} dictionary={:016x}",
+        options.hard_mode, options.prior, options.strategy, options.lookahead, hash
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Memo;
+    use crate::algorithms::{HardMode, Options};
+    use crate::{to_word, Guess};
+
+    #[test]
+    fn save_and_load() {
+        let options = Options::default();
+        let mut memo = Memo::new(&options);
+        let history = [
+            Guess {
+                word: to_word("tares"),
+                mask: "-+--#".parse().unwrap(),
+            },
+            Guess {
+                word: to_word("clout"),
+                mask: "--#--".parse().unwrap(),
+            },
+        ];
+        memo.insert(&[], to_word("tares"));
+        memo.insert(&history[..1], to_word("clout"));
+        memo.insert(&history, to_word("bumph"));
+
+        let path = std::env::temp_dir().join(format!("rogerthat-memo-{}", std::process::id()));
+        memo.save(&path).unwrap();
+        let loaded = Memo::load(&path, &options).unwrap();
+        assert_eq!(loaded.len(), 3);
+        assert_eq!(loaded.get(&[]), Some(to_word("tares")));
+        assert_eq!(loaded.get(&history[..1]), Some(to_word("clout")));
+        assert_eq!(loaded.get(&history), Some(to_word("bumph")));
+
+        let hard = Options {
+            hard_mode: HardMode::Nyt,
+            ..Options::default()
+        };
+        assert!(!loaded.is_for(&hard));
+        assert!(Memo::load(&path, &hard).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,56 +1,69 @@
-use ndarray::Array5;
-use once_cell::sync::OnceCell;
-
-use crate::{to_word, Correctness, Guess, Guesser, Word, DICTIONARY};
-use ascii::ToAsciiChar;
-use std::{borrow::Cow, collections::HashMap};
-
-static INITIAL: OnceCell<HashMap<Word, usize>> = OnceCell::new();
+use super::{entropy, likeliest, Candidate, Lookahead, Memo, Options};
+use crate::matrix::PatternMatrix;
+use crate::{Guess, Guesser, Pattern, Word};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
 
 pub struct Memoized {
-    remaining: Cow<'static, HashMap<Word, usize>>,
-    second_guess: [Option<Word>; 3 * 3 * 3 * 3 * 3],
-}
-
-fn get_mask_idx(mask: [Correctness; 5]) -> usize {
-    (mask[0] as usize) * (3 as usize).pow(0)
-        + (mask[1] as usize) * (3 as usize).pow(1)
-        + (mask[2] as usize) * (3 as usize).pow(2)
-        + (mask[3] as usize) * (3 as usize).pow(3)
-        + (mask[4] as usize) * (3 as usize).pow(4)
+    initial: Arc<HashMap<Word, f64>>,
+    remaining: Arc<HashMap<Word, f64>>,
+    options: Options,
+    /// Shared by all the guessers made for a run, and possibly saved afterwards.
+    memo: Arc<Mutex<Memo>>,
 }
 
 impl Memoized {
     pub fn new() -> Self {
+        Self::with_options(Options::default())
+    }
+
+    pub fn with_options(options: Options) -> Self {
+        let memo = Arc::new(Mutex::new(Memo::new(&options)));
+        Self::with_memo(options, memo)
+    }
+
+    /// Panics if the memo was built for other options.
+    pub fn with_memo(options: Options, memo: Arc<Mutex<Memo>>) -> Self {
+        assert!(
+            memo.lock().unwrap().is_for(&options),
+            "The memo was built for another configuration"
+        );
+        let initial = Arc::new(options.initial_weights());
         Self {
-            remaining: Cow::Borrowed(INITIAL.get_or_init(|| {
-                HashMap::from_iter(DICTIONARY.lines().map(|line| {
-                    let (word, count) = line
-                        .split_once(' ')
-                        .expect("Every line must be of the format: word + space + frequency");
-                    let word: [char; 5] = word.chars().collect::<Vec<char>>().try_into().unwrap();
-                    let count: usize = count.parse().expect("Every count should be a number");
-                    (word.map(|c| c.to_ascii_char().unwrap()), count)
-                }))
-            })),
-            second_guess: [None; 3 * 3 * 3 * 3 * 3],
+            remaining: Arc::clone(&initial),
+            initial,
+            options,
+            memo,
         }
     }
+
+    pub fn memo(&self) -> &Arc<Mutex<Memo>> {
+        &self.memo
+    }
 }
 
-#[derive(Debug, Clone, Copy)]
-struct Candidate {
-    word: Word,
-    goodness: f64,
+impl Default for Memoized {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Guesser for Memoized {
     fn guess(&mut self, history: &[Guess]) -> Word {
+        let matrix = PatternMatrix::global();
         if let Some(last) = history.last() {
-            self.remaining.to_mut().retain(|word, _| last.matches(word));
+            matrix.retain_matching(Arc::make_mut(&mut self.remaining), last);
 
             let num_remains = self.remaining.len();
-            println!("Number of remaining possibilities: {}", num_remains);
+            if self.options.verbose {
+                println!("Number of remaining possibilities: {}", num_remains);
+            }
+            if num_remains == 0 {
+                // Inconsistent patterns, not worth remembering: any word will do.
+                return likeliest(&self.initial).unwrap_or(last.word);
+            }
             // If only 1 possibility remains, return that as the guess.
             // This is essential, because otherwise,
             // any guess would be considered to be as good as any other.
@@ -59,85 +72,87 @@ impl Guesser for Memoized {
             }
         } else {
             // First guess
-            self.remaining = Cow::Borrowed(INITIAL.get().unwrap());
-            return to_word("tares");
+            self.remaining = Arc::clone(&self.initial);
         }
 
-        // retrieve memoized second guess
-        if history.len() == 1 {
-            if let Some(word) = self.second_guess[get_mask_idx(history.last().unwrap().mask)] {
+        // The guess only depends on the history (the configuration is fixed),
+        // so a guess made after the same history can be reused.
+        if let Some(word) = self.memo.lock().unwrap().get(history) {
+            if self.options.verbose {
                 println!("I remember this!");
-                return word;
             }
+            return word;
         }
 
-        let remaining_count: usize = self.remaining.iter().map(|(_, &c)| c).sum();
-
+        // Look up the matrix index of every candidate once, instead of for every guess.
+        // Sorted like in the other solvers, to add up the weights the same way.
+        let mut candidates: Vec<(usize, f64)> = self
+            .remaining
+            .iter()
+            .map(|(word, &count)| (matrix.index_of(word).unwrap(), count))
+            .collect();
+        candidates.sort_unstable_by_key(|&(candidate, _)| candidate);
+        let mut guesses: Vec<Word> = self.initial.keys().copied().collect();
+        guesses.sort_unstable();
+
+        let remaining_count: f64 = candidates.iter().map(|(_, count)| count).sum();
+        let remaining_entropy = entropy(candidates.iter().map(|(_, count)| count), remaining_count);
+
+        let mut lookahead = Lookahead::new(&self.options);
         let mut best: Option<Candidate> = None;
-        let dict = INITIAL.get().unwrap();
-        for (&word, _) in dict {
+        for word in guesses {
+            if !self
+                .options
+                .hard_mode
+                .allows(history, &word, self.remaining.contains_key(&word))
+            {
+                continue;
+            }
             // measure goodness, which is the expected value of the information
             // - SUM_i p_i * log_2(p_i)
 
-            let mut mask_buckets = Array5::<usize>::zeros((3, 3, 3, 3, 3));
-            for (candidate, count) in &*self.remaining {
-                let mask = Correctness::compute(candidate, &word);
-                mask_buckets[[
-                    mask[0] as usize,
-                    mask[1] as usize,
-                    mask[2] as usize,
-                    mask[3] as usize,
-                    mask[4] as usize,
-                ]] += count;
+            let row = matrix.row(matrix.index_of(&word).unwrap());
+            let mut mask_buckets = [0.0; Pattern::COUNT];
+            for &(candidate, count) in &candidates {
+                mask_buckets[row[candidate] as usize] += count;
             }
 
             let mut goodness = 0.0;
-            for mask in Correctness::all_patterns() {
-                let in_pattern_total = mask_buckets[[
-                    mask[0] as usize,
-                    mask[1] as usize,
-                    mask[2] as usize,
-                    mask[3] as usize,
-                    mask[4] as usize,
-                ]];
-                if in_pattern_total == 0 {
+            for in_pattern_total in mask_buckets {
+                if in_pattern_total <= 0.0 {
                     // avoid indeterminate arithmetic (NaN) which should evaluate to 0
                     continue;
                 }
-                let prob_of_pattern = (in_pattern_total as f64) / (remaining_count as f64);
+                let prob_of_pattern = in_pattern_total / remaining_count;
                 goodness -= prob_of_pattern * prob_of_pattern.log2();
             }
+            let probability = self
+                .remaining
+                .get(&word)
+                .map_or(0.0, |w| w / remaining_count);
+            // turn it into whatever the strategy optimizes
+            let goodness = self
+                .options
+                .strategy
+                .goodness(goodness, remaining_entropy, probability);
+            if let Some(lookahead) = &mut lookahead {
+                lookahead.push(word, row, goodness);
+            }
 
-            if let Some(c) = best {
-                use crate::EPSILON;
-
-                // Is this one better?
-                if goodness > c.goodness + EPSILON {
-                    best = Some(Candidate { word, goodness })
-
-                // Tie is pretty common when there are few words left.
-                // Make sure to handle this situation well.
-                } else if !(c.goodness > goodness + EPSILON) {
-                    // disfavor a word that has been ruled out
-                    // if neither word has been ruled out, favor the more common one
-                    if !self.remaining.contains_key(&c.word)
-                        || ((self.remaining.contains_key(&word))
-                            && (dict.get(&word) > dict.get(&c.word)))
-                    {
-                        best = Some(Candidate { word, goodness });
-                    }
-                }
-            } else {
-                best = Some(Candidate { word, goodness });
+            Candidate {
+                word,
+                goodness,
+                probability,
             }
+            .keep_best(&mut best);
         }
-        let guess = best.unwrap().word;
-
-        // If this is the second guess, remember it.
-        if history.len() == 1 {
-            self.second_guess[get_mask_idx(history.last().unwrap().mask)] = Some(guess);
+        let mut guess = best.unwrap().word;
+        if let Some(lookahead) = lookahead {
+            let weights: Vec<f64> = candidates.iter().map(|&(_, count)| count).collect();
+            guess = lookahead.choose(guess, &weights, |row, c| row[candidates[c].0]);
         }
 
+        self.memo.lock().unwrap().insert(history, guess);
         guess
     }
 }
@@ -0,0 +1,241 @@
+use crate::{words::WordLists, Guess, Guesser, Pattern, Word};
+use std::collections::HashMap;
+
+/// Minimizes the number of guesses in the worst case instead of on average,
+/// as needed against an adversarial host like Absurdle's, which gives
+/// whatever pattern keeps the most answers possible.
+///
+/// The guess leaving the largest bucket of answers the smallest is played,
+/// until there are few enough answers left to search exhaustively
+/// for the guess finishing in the fewest guesses, whatever the patterns.
+pub struct Minimax {
+    guesses: Vec<Word>,
+    answers: Vec<Word>,
+    /// The pattern of every guess (row) for every answer (column).
+    patterns: Vec<u8>,
+    /// The index in `guesses` of every answer.
+    answer_guess: Vec<usize>,
+    /// The least number of guesses in the worst case and a guess achieving it,
+    /// keyed by the sorted indices of the possible answers.
+    memo: HashMap<Vec<u16>, (usize, usize)>,
+    /// Print the number of answers still possible while guessing.
+    verbose: bool,
+}
+
+/// Answers left from which the search is exhaustive.
+const ENDGAME: usize = 40;
+
+impl Minimax {
+    /// For the dictionary and answers of the global word lists.
+    pub fn new() -> Self {
+        let lists = WordLists::global();
+        Self::with_words(lists.words().collect(), lists.answers.clone())
+    }
+
+    /// Panics if an answer is not one of the guesses.
+    pub fn with_words(guesses: Vec<Word>, answers: Vec<Word>) -> Self {
+        assert!(answers.len() <= u16::MAX as usize, "Too many answers");
+        let index: HashMap<Word, usize> =
+            guesses.iter().enumerate().map(|(i, &w)| (w, i)).collect();
+        let answer_guess = answers
+            .iter()
+            .map(|a| *index.get(a).expect("Every answer must be an allowed guess"))
+            .collect();
+        let patterns = guesses
+            .iter()
+            .flat_map(|g| answers.iter().map(move |a| Pattern::compute(a, g).id()))
+            .collect();
+        Self {
+            guesses,
+            answers,
+            patterns,
+            answer_guess,
+            memo: HashMap::new(),
+            verbose: false,
+        }
+    }
+
+    pub fn with_verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    fn pattern(&self, guess: usize, answer: u16) -> u8 {
+        self.patterns[guess * self.answers.len() + answer as usize]
+    }
+
+    /// The sizes of the buckets `guess` splits `candidates` into.
+    fn buckets(&self, candidates: &[u16], guess: usize, sizes: &mut [usize; Pattern::COUNT]) {
+        sizes.fill(0);
+        for &answer in candidates {
+            sizes[self.pattern(guess, answer) as usize] += 1;
+        }
+    }
+
+    /// The guess whose largest bucket (apart from the answer itself) is the smallest,
+    /// preferring the possible answers.
+    fn greedy(&self, candidates: &[u16]) -> usize {
+        let mut sizes = [0usize; Pattern::COUNT];
+        (0..self.guesses.len())
+            .min_by_key(|&guess| {
+                self.buckets(candidates, guess, &mut sizes);
+                let is_candidate = sizes[Pattern::SOLVED.index()] > 0;
+                sizes[Pattern::SOLVED.index()] = 0;
+                (*sizes.iter().max().unwrap(), !is_candidate)
+            })
+            .unwrap()
+    }
+
+    /// The least number of guesses that surely finds the answer among `candidates`,
+    /// and a guess achieving it, or `None` if it takes more than `depth` guesses.
+    fn solve(&mut self, candidates: &[u16], depth: usize) -> Option<(usize, usize)> {
+        if depth == 0 {
+            return None;
+        }
+        if candidates.len() == 1 {
+            return Some((1, self.answer_guess[candidates[0] as usize]));
+        }
+        if depth == 1 {
+            return None;
+        }
+        if let Some(&(guesses, guess)) = self.memo.get(candidates) {
+            return (guesses <= depth).then_some((guesses, guess));
+        }
+
+        // The guesses leaving the smallest buckets first.
+        let mut options = Vec::new();
+        let mut sizes = [0usize; Pattern::COUNT];
+        for guess in 0..self.guesses.len() {
+            self.buckets(candidates, guess, &mut sizes);
+            let is_candidate = sizes[Pattern::SOLVED.index()] > 0;
+            sizes[Pattern::SOLVED.index()] = 0;
+            let largest = *sizes.iter().max().unwrap();
+            if largest < candidates.len() {
+                options.push((largest, !is_candidate, guess));
+            }
+        }
+        options.sort_unstable();
+
+        let mut best: Option<(usize, usize)> = None;
+        let mut depth = depth;
+        let mut buckets: Vec<Vec<u16>> = vec![Vec::new(); Pattern::COUNT];
+        'options: for (largest, _, guess) in options {
+            // Telling them all apart, or not even that.
+            let at_least = if largest == 1 { 2 } else { 3 };
+            if at_least > depth {
+                // The options are sorted, so the later ones can't do better.
+                break;
+            }
+            for bucket in &mut buckets {
+                bucket.clear();
+            }
+            for &answer in candidates {
+                buckets[self.pattern(guess, answer) as usize].push(answer);
+            }
+            buckets[Pattern::SOLVED.index()].clear();
+
+            let mut worst = 1;
+            for bucket in buckets.iter().filter(|b| !b.is_empty()) {
+                match self.solve(bucket, depth - 1) {
+                    Some((guesses, _)) => worst = worst.max(1 + guesses),
+                    None => continue 'options,
+                }
+            }
+            best = Some((worst, guess));
+            if worst == at_least {
+                break;
+            }
+            depth = worst - 1;
+        }
+
+        // Only exact results are kept, so that a larger depth can't be wrongly refused.
+        if let Some(best) = best {
+            self.memo.insert(candidates.to_vec(), best);
+        }
+        best
+    }
+}
+
+impl Default for Minimax {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Guesser for Minimax {
+    fn guess(&mut self, history: &[Guess]) -> Word {
+        let candidates: Vec<u16> = (0..self.answers.len() as u16)
+            .filter(|&a| history.iter().all(|g| g.matches(&self.answers[a as usize])))
+            .collect();
+        if self.verbose {
+            println!("Number of remaining possibilities: {}", candidates.len());
+        }
+
+        let guess = match candidates.len() {
+            // The answer is not one of ours: guess anything that could still be it.
+            0 => {
+                return self
+                    .guesses
+                    .iter()
+                    .copied()
+                    .find(|w| history.iter().all(|g| g.matches(w)))
+                    .unwrap_or(self.guesses[0])
+            }
+            n if n <= ENDGAME => match self.solve(&candidates, n) {
+                Some((_, guess)) => guess,
+                None => self.greedy(&candidates),
+            },
+            _ => self.greedy(&candidates),
+        };
+        self.guesses[guess]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Minimax;
+    use crate::{to_word, Pattern, Word};
+
+    /// The least number of guesses in the worst case, trying every guess at every node.
+    fn brute_force(guesses: &[Word], answers: &[Word]) -> usize {
+        if answers.len() == 1 {
+            return 1;
+        }
+        let mut best = usize::MAX;
+        for guess in guesses {
+            let mut buckets: Vec<Vec<Word>> = vec![Vec::new(); Pattern::COUNT];
+            for answer in answers {
+                buckets[Pattern::compute(answer, guess).index()].push(*answer);
+            }
+            buckets[Pattern::SOLVED.index()].clear();
+            if buckets.iter().any(|b| b.len() == answers.len()) {
+                continue;
+            }
+            let worst = buckets
+                .iter()
+                .filter(|b| !b.is_empty())
+                .map(|b| 1 + brute_force(guesses, b))
+                .max()
+                .unwrap_or(1);
+            best = best.min(worst);
+        }
+        best
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let answers: Vec<Word> = [
+            "right", "wight", "tight", "fight", "might", "eight", "light",
+        ]
+        .map(to_word)
+        .into();
+        let mut guesses = answers.clone();
+        guesses.extend(["swift", "month", "gleam"].map(to_word));
+        let mut minimax = Minimax::with_words(guesses.clone(), answers.clone());
+        let all: Vec<u16> = (0..answers.len() as u16).collect();
+        let (worst, _) = minimax.solve(&all, answers.len()).unwrap();
+        assert_eq!(worst, brute_force(&guesses, &answers));
+        // One at a time would take all of them.
+        assert!(worst < answers.len());
+    }
+}
@@ -0,0 +1,170 @@
+use super::{entropy, opener, Candidate, Options, Strategy};
+use crate::matrix::PatternMatrix;
+use crate::{Guess, MultiGuesser, Pattern, Word};
+use std::{collections::HashMap, sync::Arc};
+
+/// Plays Dordle, Quordle, Octordle and the like: every guess is scored
+/// against the answers of all the boards at once.
+///
+/// Every board keeps its own possible answers, and a guess is as good as
+/// the sum of what it is worth on every board not solved yet.
+/// Hard mode doesn't apply: the multi-board games have no such rule.
+pub struct MultiBoard {
+    initial: Arc<HashMap<Word, f64>>,
+    /// The possible answers of every board, or `None` once it is solved.
+    boards: Vec<Option<Arc<HashMap<Word, f64>>>>,
+    options: Options,
+}
+
+impl MultiBoard {
+    pub fn new(boards: usize) -> Self {
+        Self::with_options(boards, Options::default())
+    }
+
+    pub fn with_options(boards: usize, options: Options) -> Self {
+        assert!(boards > 0, "There must be at least one board");
+        let initial = Arc::new(options.initial_weights());
+        Self {
+            boards: vec![Some(Arc::clone(&initial)); boards],
+            initial,
+            options,
+        }
+    }
+
+    pub fn num_boards(&self) -> usize {
+        self.boards.len()
+    }
+
+    /// The possible answers of every board, `None` for the solved ones.
+    pub fn remaining(&self) -> impl Iterator<Item = Option<&HashMap<Word, f64>>> {
+        self.boards.iter().map(|board| board.as_deref())
+    }
+}
+
+/// A board not solved yet, as seen by the guess being chosen.
+struct Board<'a> {
+    remaining: &'a HashMap<Word, f64>,
+    /// The matrix index and weight of every possible answer.
+    candidates: Vec<(usize, f64)>,
+    total: f64,
+    entropy: f64,
+}
+
+impl MultiGuesser for MultiBoard {
+    fn guess(&mut self, histories: &[Vec<Guess>]) -> Word {
+        assert_eq!(histories.len(), self.boards.len(), "Wrong number of boards");
+        let matrix = PatternMatrix::global();
+        let turn = histories.iter().map(Vec::len).max().unwrap_or(0);
+        if turn == 0 {
+            // First guess
+            for board in &mut self.boards {
+                *board = Some(Arc::clone(&self.initial));
+            }
+            // The boards are all alike, so only their number matters.
+            let boards = format!("boards={}", self.boards.len());
+            return opener(&self.options, &boards, || self.best());
+        } else {
+            for (board, history) in self.boards.iter_mut().zip(histories) {
+                // Solved boards don't get the guesses after the all-green one.
+                let Some(last) = history.last().filter(|_| history.len() == turn) else {
+                    continue;
+                };
+                if last.mask.is_solved() {
+                    *board = None;
+                } else if let Some(remaining) = board {
+                    matrix.retain_matching(Arc::make_mut(remaining), last);
+                }
+            }
+            if self.options.verbose {
+                println!(
+                    "Number of remaining possibilities: {}",
+                    self.boards
+                        .iter()
+                        .map(|board| board
+                            .as_ref()
+                            .map_or("-".to_string(), |r| r.len().to_string()))
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                );
+            }
+        }
+        self.best()
+    }
+}
+
+impl MultiBoard {
+    /// The best guess for the possible answers left on the boards.
+    fn best(&self) -> Word {
+        let matrix = PatternMatrix::global();
+        // A board with a single possibility left is solved with that guess:
+        // nothing can do better than finishing a board right away.
+        if let Some(word) = self
+            .boards
+            .iter()
+            .flatten()
+            .find(|remaining| remaining.len() == 1)
+            .map(|remaining| *remaining.keys().next().unwrap())
+        {
+            return word;
+        }
+
+        // Boards left without any possibility (because of wrong patterns) tell nothing.
+        let boards: Vec<Board> = self
+            .boards
+            .iter()
+            .flatten()
+            .filter(|remaining| !remaining.is_empty())
+            .map(|remaining| {
+                // Sorted: the total and the entropy must not depend on the hashing.
+                let mut candidates: Vec<(usize, f64)> = remaining
+                    .iter()
+                    .map(|(word, &weight)| (matrix.index_of(word).unwrap(), weight))
+                    .collect();
+                candidates.sort_unstable_by_key(|&(candidate, _)| candidate);
+                let total: f64 = candidates.iter().map(|(_, weight)| weight).sum();
+                Board {
+                    remaining,
+                    entropy: entropy(candidates.iter().map(|(_, weight)| weight), total),
+                    candidates,
+                    total,
+                }
+            })
+            .collect();
+        let mut guesses: Vec<Word> = self.initial.keys().copied().collect();
+        guesses.sort_unstable();
+
+        let mut best: Option<Candidate> = None;
+        for word in guesses {
+            let row = matrix.row(matrix.index_of(&word).unwrap());
+            let mut goodness = 0.0;
+            // Of being the answer of one of the boards, for ties.
+            let mut probability = 0.0;
+            for board in &boards {
+                let mut mask_buckets = [0.0; Pattern::COUNT];
+                for &(candidate, weight) in &board.candidates {
+                    mask_buckets[row[candidate] as usize] += weight;
+                }
+                let information = entropy(mask_buckets.iter(), board.total);
+                let prob_of_answer = board.remaining.get(&word).map_or(0.0, |w| w / board.total);
+                probability += prob_of_answer;
+                goodness +=
+                    self.options
+                        .strategy
+                        .goodness(information, board.entropy, prob_of_answer);
+                if self.options.strategy == Strategy::Information {
+                    // The information doesn't count that finishing a board saves
+                    // the guess it would otherwise need: count it as one more bit.
+                    goodness += prob_of_answer;
+                }
+            }
+
+            Candidate {
+                word,
+                goodness,
+                probability,
+            }
+            .keep_best(&mut best);
+        }
+        best.unwrap().word
+    }
+}
@@ -1,31 +1,33 @@
-use crate::{to_word, Correctness, Guess, Guesser, Word, DICTIONARY};
-use ascii::ToAsciiChar;
+use super::{entropy, likeliest, opener, Candidate, MaskBuckets, Options};
+use crate::{Guess, Guesser, Pattern, Word};
 use std::collections::HashMap;
 
 pub struct Naive {
-    initial: HashMap<Word, usize>,
-    remaining: HashMap<Word, usize>,
+    initial: HashMap<Word, f64>,
+    remaining: HashMap<Word, f64>,
+    options: Options,
 }
 
 impl Naive {
     pub fn new() -> Self {
-        let initial = HashMap::from_iter(DICTIONARY.lines().map(|line| {
-            let (word, count) = line
-                .split_once(' ')
-                .expect("Every line must be of the format: word + space + frequency");
-            let word: [char; 5] = word.chars().collect::<Vec<char>>().try_into().unwrap();
-            let count: usize = count.parse().expect("Every count should be a number");
-            (word.map(|c| c.to_ascii_char().unwrap()), count)
-        }));
+        Self::with_options(Options::default())
+    }
+
+    pub fn with_options(options: Options) -> Self {
+        let initial = options.initial_weights();
         let remaining = initial.clone();
-        Self { initial, remaining }
+        Self {
+            initial,
+            remaining,
+            options,
+        }
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-struct Candidate {
-    word: Word,
-    goodness: f64,
+impl Default for Naive {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Guesser for Naive {
@@ -34,7 +36,14 @@ impl Guesser for Naive {
             self.remaining.retain(|word, _| last.matches(word));
 
             let num_remains = self.remaining.len();
-            println!("Number of remaining possibilities: {}", num_remains);
+            if self.options.verbose {
+                println!("Number of remaining possibilities: {}", num_remains);
+            }
+            if num_remains == 0 {
+                // The patterns contradict each other (a wrong history, or a misbehaving host),
+                // so no word is allowed in strict hard mode: any word will do.
+                return likeliest(&self.initial).unwrap_or(last.word);
+            }
             // If only 1 possibility remains, return that as the guess.
             // This is essential, because otherwise,
             // any guess would be considered to be as good as any other.
@@ -44,19 +53,45 @@ impl Guesser for Naive {
         } else {
             // First guess
             self.remaining = self.initial.clone();
-            return to_word("tares");
+            // Computing it the naive way would take hours, and the pattern matrix gives
+            // the same guess (without the lookahead, which this solver ignores).
+            let options = Options {
+                lookahead: None,
+                ..self.options.clone()
+            };
+            return opener(&options, "", || {
+                MaskBuckets::with_options(options.clone()).best(history)
+            });
         }
 
-        let remaining_count: usize = self.remaining.iter().map(|(_, &c)| c).sum();
+        // In a fixed order: sums of floats depend on it, and so would the guess.
+        let mut candidates: Vec<(Word, f64)> = self
+            .remaining
+            .iter()
+            .map(|(&word, &count)| (word, count))
+            .collect();
+        candidates.sort_unstable_by_key(|&(word, _)| word);
+        let mut guesses: Vec<Word> = self.initial.keys().copied().collect();
+        guesses.sort_unstable();
+
+        let remaining_count: f64 = candidates.iter().map(|(_, count)| count).sum();
+        let remaining_entropy = entropy(candidates.iter().map(|(_, count)| count), remaining_count);
 
         let mut best: Option<Candidate> = None;
-        for (&word, _) in &self.initial {
+        for word in guesses {
+            if !self
+                .options
+                .hard_mode
+                .allows(history, &word, self.remaining.contains_key(&word))
+            {
+                continue;
+            }
             // measure goodness, which is the expected value of the information
             // - SUM_i p_i * log_2(p_i)
             let mut goodness = 0.0;
-            for pattern in Correctness::all_patterns() {
-                let mut in_pattern_total = 0;
-                for (candidate, count) in &self.remaining {
+            for pattern in Pattern::feasible() {
+                let mut in_pattern_total = 0.0;
+                for (candidate, count) in &candidates {
                     let g = Guess {
                         word,
                         mask: pattern,
@@ -65,37 +100,52 @@ impl Guesser for Naive {
                         in_pattern_total += count;
                     }
                 }
-                if in_pattern_total == 0 {
+                if in_pattern_total <= 0.0 {
                     // avoid indeterminate arithmetic (NaN) which should evaluate to 0
                     continue;
                 }
-                let prob_of_pattern = in_pattern_total as f64 / remaining_count as f64;
+                let prob_of_pattern = in_pattern_total / remaining_count;
                 goodness -= prob_of_pattern * prob_of_pattern.log2();
             }
+            let probability = self
+                .remaining
+                .get(&word)
+                .map_or(0.0, |w| w / remaining_count);
+            // turn it into whatever the strategy optimizes
+            let goodness = self
+                .options
+                .strategy
+                .goodness(goodness, remaining_entropy, probability);
 
-            if let Some(c) = best {
-                use crate::EPSILON;
-
-                // Is this one better?
-                if goodness > c.goodness + EPSILON {
-                    best = Some(Candidate { word, goodness })
-
-                // Tie is pretty common when there are few words left.
-                // Make sure to handle this situation well.
-                } else if !(c.goodness > goodness + EPSILON) {
-                    // disfavor a word that has been ruled out
-                    // if neither word has been ruled out, favor the more common one
-                    if !self.remaining.contains_key(&c.word)
-                        || ((self.remaining.contains_key(&word))
-                            && (self.initial.get(&word) > self.initial.get(&c.word)))
-                    {
-                        best = Some(Candidate { word, goodness });
-                    }
-                }
-            } else {
-                best = Some(Candidate { word, goodness });
+            Candidate {
+                word,
+                goodness,
+                probability,
             }
+            .keep_best(&mut best);
         }
         best.unwrap().word
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Naive;
+    use crate::algorithms::{HardMode, Options};
+    use crate::{to_word, Guess, Guesser};
+
+    #[test]
+    fn contradiction() {
+        let mut naive = Naive::with_options(Options {
+            hard_mode: HardMode::Strict,
+            ..Options::default()
+        });
+        // The 's' is somewhere else, but every other spot is taken.
+        let history = [Guess {
+            word: to_word("tares"),
+            mask: "####+".parse().unwrap(),
+        }];
+        let guess = naive.guess(&history);
+        assert!(naive.initial.contains_key(&guess));
+    }
+}
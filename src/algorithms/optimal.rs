@@ -0,0 +1,323 @@
+use crate::{words::WordLists, Guess, Guesser, Pattern, Word};
+use std::collections::HashMap;
+
+/// Plays the strategy that minimizes the average number of guesses over the answers,
+/// found by an exhaustive branch-and-bound search of the decision trees.
+///
+/// Unlike the other solvers, the answers are known and equally likely,
+/// so this is the ground truth the heuristics can be measured against.
+/// The search is exact, which means that it is only practical for small answer lists.
+pub struct Optimal {
+    guesses: Vec<Word>,
+    answers: Vec<Word>,
+    /// The pattern of every guess (row) for every answer (column).
+    patterns: Vec<u8>,
+    /// The index in `guesses` of every answer.
+    answer_guess: Vec<usize>,
+    /// At most this many guesses per game, if set.
+    worst_case: Option<usize>,
+    /// Keyed by the sorted indices of the possible answers and the guesses left.
+    memo: HashMap<(Vec<u16>, usize), Solution>,
+    /// Print the number of answers still possible while guessing.
+    verbose: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Solution {
+    /// The least total number of guesses, and a guess that achieves it.
+    Exact(usize, usize),
+    /// There is no strategy with fewer guesses in total.
+    AtLeast(usize),
+}
+
+/// Guesses left when there is no worst-case bound. It is never decremented,
+/// so that the memoized solutions are shared between all depths.
+const UNBOUNDED: usize = usize::MAX;
+
+impl Optimal {
+    /// For the dictionary and answers of the global word lists.
+    pub fn new() -> Self {
+        let lists = WordLists::global();
+        Self::with_words(lists.words().collect(), lists.answers.clone())
+    }
+
+    /// Panics if an answer is not one of the guesses.
+    pub fn with_words(guesses: Vec<Word>, answers: Vec<Word>) -> Self {
+        assert!(answers.len() <= u16::MAX as usize, "Too many answers");
+        let index: HashMap<Word, usize> =
+            guesses.iter().enumerate().map(|(i, &w)| (w, i)).collect();
+        let answer_guess = answers
+            .iter()
+            .map(|a| *index.get(a).expect("Every answer must be an allowed guess"))
+            .collect();
+        let patterns = guesses
+            .iter()
+            .flat_map(|g| answers.iter().map(move |a| Pattern::compute(a, g).id()))
+            .collect();
+        Self {
+            guesses,
+            answers,
+            patterns,
+            answer_guess,
+            worst_case: None,
+            memo: HashMap::new(),
+            verbose: false,
+        }
+    }
+
+    pub fn with_verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Only consider strategies that find every answer within `guesses` guesses.
+    pub fn with_worst_case(mut self, guesses: usize) -> Self {
+        self.worst_case = Some(guesses);
+        self.memo.clear();
+        self
+    }
+
+    /// The least total number of guesses to find every answer,
+    /// or `None` if the worst-case bound can't be met.
+    pub fn total_guesses(&mut self) -> Option<usize> {
+        let all: Vec<u16> = (0..self.answers.len() as u16).collect();
+        let depth = self.worst_case.unwrap_or(UNBOUNDED);
+        self.solve(&all, depth, usize::MAX).map(|(total, _)| total)
+    }
+
+    pub fn average_guesses(&mut self) -> Option<f64> {
+        let n = self.answers.len() as f64;
+        self.total_guesses().map(|total| total as f64 / n)
+    }
+
+    fn pattern(&self, guess: usize, answer: u16) -> u8 {
+        self.patterns[guess * self.answers.len() + answer as usize]
+    }
+
+    /// The least total number of guesses to find every one of `candidates`
+    /// with at most `depth` guesses each, and a guess achieving it,
+    /// provided that it is less than `bound`.
+    fn solve(&mut self, candidates: &[u16], depth: usize, bound: usize) -> Option<(usize, usize)> {
+        let n = candidates.len();
+        if depth == 0 {
+            return None;
+        }
+        if n == 1 {
+            return (1 < bound).then_some((1, self.answer_guess[candidates[0] as usize]));
+        }
+        if depth == 1 {
+            return None;
+        }
+        if n == 2 {
+            // Guess one of them: it's the answer, or the other one is next.
+            return (3 < bound).then_some((3, self.answer_guess[candidates[0] as usize]));
+        }
+
+        let key = (candidates.to_vec(), depth);
+        match self.memo.get(&key) {
+            Some(&Solution::Exact(total, guess)) => {
+                return (total < bound).then_some((total, guess))
+            }
+            Some(&Solution::AtLeast(total)) if total >= bound => return None,
+            _ => {}
+        }
+        let next_depth = if depth == UNBOUNDED { depth } else { depth - 1 };
+
+        // A lower bound for every guess, to try the most promising ones first
+        // and skip the ones that can't beat the best so far.
+        let mut options = Vec::new();
+        let mut sizes = [0usize; Pattern::COUNT];
+        'guesses: for guess in 0..self.guesses.len() {
+            sizes.fill(0);
+            for &answer in candidates {
+                sizes[self.pattern(guess, answer) as usize] += 1;
+            }
+            let is_candidate = sizes[Pattern::SOLVED.index()] > 0;
+            let mut lower_bound = n;
+            for (pattern, &size) in sizes.iter().enumerate() {
+                if size == 0 || pattern == Pattern::SOLVED.index() {
+                    continue;
+                }
+                if size == n || (size > 1 && next_depth == 1) {
+                    // No progress, or no way to tell them apart in time.
+                    continue 'guesses;
+                }
+                // At most one of them is found with the next guess.
+                lower_bound += 2 * size - 1;
+            }
+            options.push((lower_bound, !is_candidate, guess));
+        }
+        options.sort_unstable();
+
+        let mut best: Option<(usize, usize)> = None;
+        let mut bound = bound;
+        let mut buckets: Vec<Vec<u16>> = vec![Vec::new(); Pattern::COUNT];
+        'options: for (lower_bound, _, guess) in options {
+            if lower_bound >= bound {
+                break;
+            }
+            for bucket in &mut buckets {
+                bucket.clear();
+            }
+            for &answer in candidates {
+                buckets[self.pattern(guess, answer) as usize].push(answer);
+            }
+            buckets[Pattern::SOLVED.index()].clear();
+
+            // Replace the lower bound of every bucket with its exact cost, one at a time.
+            let mut total = lower_bound;
+            for bucket in buckets.iter().filter(|b| !b.is_empty()) {
+                let estimate = if bucket.len() == 1 {
+                    1
+                } else {
+                    2 * bucket.len() - 1
+                };
+                let rest = total - estimate;
+                match self.solve(bucket, next_depth, bound - rest) {
+                    Some((cost, _)) => total = rest + cost,
+                    None => continue 'options,
+                }
+            }
+            best = Some((total, guess));
+            bound = total;
+        }
+
+        self.memo.insert(
+            key,
+            match best {
+                Some((total, guess)) => Solution::Exact(total, guess),
+                None => Solution::AtLeast(bound),
+            },
+        );
+        best
+    }
+}
+
+impl Default for Optimal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Guesser for Optimal {
+    fn guess(&mut self, history: &[Guess]) -> Word {
+        let candidates: Vec<u16> = (0..self.answers.len() as u16)
+            .filter(|&a| history.iter().all(|g| g.matches(&self.answers[a as usize])))
+            .collect();
+        if self.verbose {
+            println!("Number of remaining possibilities: {}", candidates.len());
+        }
+
+        let depth = match self.worst_case {
+            Some(guesses) => guesses.saturating_sub(history.len()),
+            None => UNBOUNDED,
+        };
+        match self.solve(&candidates, depth, usize::MAX) {
+            Some((_, guess)) => self.guesses[guess],
+            // The answer is not one of ours, or the bound can't be met anymore:
+            // guess anything that could still be the answer.
+            None => self
+                .guesses
+                .iter()
+                .copied()
+                .find(|w| history.iter().all(|g| g.matches(w)))
+                .unwrap_or(self.guesses[0]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Optimal;
+    use crate::{to_word, Pattern, Word};
+    use std::collections::HashMap;
+
+    /// The least total number of guesses, trying every guess at every node.
+    fn brute_force(guesses: &[Word], answers: &[Word], depth: usize) -> Option<usize> {
+        if answers.len() == 1 {
+            return Some(1);
+        }
+        if depth <= 1 {
+            return None;
+        }
+        let mut best = None;
+        for guess in guesses {
+            let mut buckets: HashMap<Pattern, Vec<Word>> = HashMap::new();
+            for answer in answers {
+                buckets
+                    .entry(Pattern::compute(answer, guess))
+                    .or_default()
+                    .push(*answer);
+            }
+            if buckets.len() == 1 && !answers.contains(guess) {
+                continue;
+            }
+            let mut total = Some(answers.len());
+            for (pattern, bucket) in buckets {
+                if !pattern.is_solved() {
+                    total = total
+                        .zip(brute_force(guesses, &bucket, depth - 1))
+                        .map(|(a, b)| a + b);
+                }
+            }
+            if let Some(total) = total {
+                best = Some(best.map_or(total, |b: usize| b.min(total)));
+            }
+        }
+        best
+    }
+
+    fn words() -> (Vec<Word>, Vec<Word>) {
+        let answers: Vec<Word> = [
+            "right", "wight", "tight", "fight", "might", "eight", "light",
+        ]
+        .map(to_word)
+        .into();
+        let mut guesses = answers.clone();
+        guesses.extend(["swift", "month", "gleam"].map(to_word));
+        (guesses, answers)
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let (guesses, answers) = words();
+        let mut optimal = Optimal::with_words(guesses.clone(), answers.clone());
+        let total = optimal.total_guesses();
+        assert_eq!(total, brute_force(&guesses, &answers, answers.len()));
+        // Guessing the ?ight words one after another is far from it.
+        assert!(total.unwrap() < (1..=answers.len()).sum());
+
+        for worst_case in 2..5 {
+            let mut bounded =
+                Optimal::with_words(guesses.clone(), answers.clone()).with_worst_case(worst_case);
+            assert_eq!(
+                bounded.total_guesses(),
+                brute_force(&guesses, &answers, worst_case)
+            );
+        }
+    }
+
+    #[test]
+    fn plays_the_tree() {
+        let (guesses, answers) = words();
+        let mut optimal = Optimal::with_words(guesses.clone(), answers.clone());
+        let total = optimal.total_guesses().unwrap();
+        let mut played = 0;
+        for answer in &answers {
+            let mut history = Vec::new();
+            loop {
+                use crate::Guesser;
+                let guess = optimal.guess(&history);
+                history.push(crate::Guess {
+                    word: guess,
+                    mask: Pattern::compute(answer, &guess),
+                });
+                if guess == *answer {
+                    break;
+                }
+            }
+            played += history.len();
+        }
+        assert_eq!(played, total);
+    }
+}
@@ -0,0 +1,252 @@
+use crate::{game::hard_mode_violation, words::WordLists, Guess, Pattern, Word};
+use std::{collections::HashMap, fmt::Debug, sync::Arc};
+
+/// Which words a solver may guess once it has received some patterns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HardMode {
+    /// Any word of the dictionary.
+    #[default]
+    Off,
+    /// The NYT rule: revealed hints must be used (see `game::hard_mode_violation`),
+    /// but the word need not be a possible answer.
+    Nyt,
+    /// Only the words that could still be the answer.
+    Strict,
+}
+
+impl HardMode {
+    /// Whether `word` may be guessed after `history`.
+    /// `is_possible` tells whether it could still be the answer.
+    #[inline]
+    pub fn allows(self, history: &[Guess], word: &Word, is_possible: bool) -> bool {
+        match self {
+            HardMode::Off => true,
+            HardMode::Nyt => hard_mode_violation(history, word).is_none(),
+            HardMode::Strict => is_possible,
+        }
+    }
+}
+
+/// What the solvers optimize when choosing a guess.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Strategy {
+    /// Maximize the expected information of the guess.
+    #[default]
+    Information,
+    /// Minimize the expected number of guesses, estimating the guesses needed
+    /// after this one from the entropy left, as if every guess were ideal.
+    Optimistic,
+    /// Like `Optimistic`, but with the guesses that the information strategy
+    /// roughly needs for that much entropy (a hand-picked curve).
+    Prudent,
+}
+
+impl Strategy {
+    /// Estimated number of guesses still needed when `entropy` bits are left.
+    pub fn guesses_left(self, entropy: f64) -> f64 {
+        match self {
+            // The last guess, plus one for every log2(243) ≈ 7.9 bits: the most a guess
+            // can tell, when it splits the answers evenly over all the patterns.
+            Strategy::Information | Strategy::Optimistic => {
+                1.0 + entropy / (Pattern::COUNT as f64).log2()
+            }
+            // A curve picked by hand, not fitted: one guess when the answer is known,
+            // then slowly growing, to about 2.1 guesses at 1.5 bits and 3.8 at 9.1 bits.
+            Strategy::Prudent => 1.0 + 0.83 * (1.0 + entropy).log2(),
+        }
+    }
+
+    /// How good a guess is (the higher, the better), given its expected `information`,
+    /// the `entropy` of the possible answers, and the probability that it is the answer.
+    #[inline]
+    pub fn goodness(self, information: f64, entropy: f64, prob_of_answer: f64) -> f64 {
+        match self {
+            Strategy::Information => information,
+            _ => {
+                // This guess plus, if it isn't the answer, the ones after it.
+                let entropy_left = (entropy - information).max(0.0);
+                -(1.0 + (1.0 - prob_of_answer) * self.guesses_left(entropy_left))
+            }
+        }
+    }
+}
+
+/// The entropy (in bits) of the distribution given by `weights`, which sum up to `total`.
+pub fn entropy<'a>(weights: impl Iterator<Item = &'a f64>, total: f64) -> f64 {
+    weights
+        .filter(|&&w| w > 0.0)
+        .map(|&w| {
+            let p = w / total;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// The word with the highest weight, the first in alphabetical order on ties.
+pub(crate) fn likeliest(weights: &HashMap<Word, f64>) -> Option<Word> {
+    weights
+        .iter()
+        .max_by(|a, b| a.1.total_cmp(b.1).then(b.0.cmp(a.0)))
+        .map(|(&word, _)| word)
+}
+
+/// How likely every word of the dictionary is to be the answer.
+pub trait Prior: Debug + Send + Sync {
+    /// A weight for every entry of `dictionary` (word and corpus count),
+    /// proportional to the probability of the word being the answer.
+    fn weights(&self, dictionary: &[(Word, usize)]) -> Vec<f64>;
+}
+
+/// The probability of a word is proportional to its count in the corpus.
+#[derive(Debug, Clone, Copy)]
+pub struct Frequency;
+
+impl Prior for Frequency {
+    fn weights(&self, dictionary: &[(Word, usize)]) -> Vec<f64> {
+        dictionary.iter().map(|&(_, count)| count as f64).collect()
+    }
+}
+
+/// Words are ranked by their count in the corpus (most common first),
+/// and the rank is mapped through a decreasing sigmoid: the most common words
+/// are all about equally likely, and the rare ones are all about impossible.
+#[derive(Debug, Clone, Copy)]
+pub struct Sigmoid {
+    /// The rank at which a word is half as likely as the most common ones.
+    pub midpoint: f64,
+    /// How many ranks it takes to go from likely to unlikely.
+    pub width: f64,
+}
+
+impl Default for Sigmoid {
+    fn default() -> Self {
+        Self {
+            midpoint: 3000.0,
+            width: 500.0,
+        }
+    }
+}
+
+impl Prior for Sigmoid {
+    fn weights(&self, dictionary: &[(Word, usize)]) -> Vec<f64> {
+        // Otherwise, the weights would be NaN, or the rare words would be the likely ones.
+        assert!(
+            self.midpoint.is_finite() && self.width > 0.0 && self.width.is_finite(),
+            "Invalid sigmoid: {:?}",
+            self
+        );
+        let mut by_count: Vec<usize> = (0..dictionary.len()).collect();
+        by_count.sort_by_key(|&i| std::cmp::Reverse(dictionary[i].1));
+
+        let mut weights = vec![0.0; dictionary.len()];
+        for (rank, i) in by_count.into_iter().enumerate() {
+            weights[i] = 1.0 / (1.0 + ((rank as f64 - self.midpoint) / self.width).exp());
+        }
+        weights
+    }
+}
+
+/// Settings shared by all solvers. They don't change the speed-ups
+/// that distinguish the solvers, so the same options give the same guesses.
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub hard_mode: HardMode,
+    pub prior: Arc<dyn Prior>,
+    pub strategy: Strategy,
+    /// Re-rank the guesses with the best expected information, up to this many,
+    /// by the expected information of the guess and the best follow-up guess together.
+    /// Only meaningful with `Strategy::Information` in normal mode (the follow-ups
+    /// aren't checked against the hard mode), and ignored by the solvers
+    /// that don't use the pattern matrix (it would take them forever).
+    pub lookahead: Option<usize>,
+    /// Print the number of words still possible, and the like, while guessing.
+    /// It doesn't change the guesses.
+    pub verbose: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            hard_mode: HardMode::default(),
+            prior: Arc::new(Frequency),
+            strategy: Strategy::default(),
+            lookahead: None,
+            verbose: false,
+        }
+    }
+}
+
+impl Options {
+    /// The prior weight of every word of the dictionary.
+    pub fn initial_weights(&self) -> HashMap<Word, f64> {
+        let dictionary = &WordLists::global().dictionary;
+        let weights = self.prior.weights(dictionary);
+        dictionary
+            .iter()
+            .zip(weights)
+            .map(|(&(word, _), weight)| (word, weight))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HardMode, Prior, Sigmoid, Strategy};
+    use crate::{to_word, Guess};
+
+    #[test]
+    fn sigmoid() {
+        let dictionary = [
+            (to_word("rarer"), 1),
+            (to_word("usual"), 1000),
+            (to_word("often"), 500),
+        ];
+        let weights = Sigmoid {
+            midpoint: 1.0,
+            width: 0.5,
+        }
+        .weights(&dictionary);
+        // Ranks: usual, often, rare.
+        assert!((weights[2] - 0.5).abs() < 1e-12);
+        assert!(weights[1] > 0.85 && weights[0] < 0.15);
+        assert!((weights[1] + weights[0] - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn endgame() {
+        // Two equally likely answers left: guessing one of them takes 1.5 guesses on average.
+        for strategy in [Strategy::Optimistic, Strategy::Prudent] {
+            let possible = strategy.goodness(1.0, 1.0, 0.5);
+            assert!((possible + 1.5).abs() < 1e-12);
+            // Another word splitting them is worse, even though it is just as informative.
+            assert!(strategy.goodness(1.0, 1.0, 0.0) < possible);
+            // The more entropy is left, the more guesses it takes, with no upper bound.
+            assert_eq!(strategy.guesses_left(0.0), 1.0);
+            assert!(strategy.guesses_left(3.0) < strategy.guesses_left(9.0));
+            assert!(strategy.guesses_left(9.0) > 2.0);
+        }
+        // The points quoted for the prudent curve.
+        assert!((Strategy::Prudent.guesses_left(1.5) - 2.1).abs() < 0.05);
+        assert!((Strategy::Prudent.guesses_left(9.1) - 3.8).abs() < 0.05);
+        assert!(
+            Strategy::Prudent.goodness(2.0, 5.0, 0.1)
+                < Strategy::Optimistic.goodness(2.0, 5.0, 0.1)
+        );
+        assert_eq!(Strategy::Information.goodness(2.0, 5.0, 0.1), 2.0);
+    }
+
+    #[test]
+    fn allows() {
+        let history = [Guess {
+            word: to_word("thing"),
+            mask: "+++-+".parse().unwrap(),
+        }];
+        // Consistent with the hints, but not a possible answer.
+        let tight = to_word("tight");
+        assert!(HardMode::Off.allows(&history, &to_word("wrong"), false));
+        assert!(!HardMode::Nyt.allows(&history, &to_word("wrong"), false));
+        assert!(HardMode::Nyt.allows(&history, &tight, false));
+        assert!(!HardMode::Strict.allows(&history, &tight, false));
+        assert!(HardMode::Strict.allows(&history, &to_word("right"), true));
+    }
+}
@@ -0,0 +1,40 @@
+use crate::{tree::Tree, words::WordLists, Guess, Guesser, Word};
+
+/// Plays from a strategy tree, which takes no time at all.
+pub struct TreeGuesser {
+    tree: Tree,
+    /// Say when the tree has no guess for the history.
+    verbose: bool,
+}
+
+impl TreeGuesser {
+    pub fn new(tree: Tree) -> Self {
+        Self {
+            tree,
+            verbose: false,
+        }
+    }
+
+    pub fn with_verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+}
+
+impl Guesser for TreeGuesser {
+    fn guess(&mut self, history: &[Guess]) -> Word {
+        match self.tree.get(history) {
+            Some(node) => node.guess,
+            None => {
+                // The tree was built for other answers, or with other word lists.
+                if self.verbose {
+                    println!("This is not in the tree, guessing the first possible word.");
+                }
+                WordLists::global()
+                    .words()
+                    .find(|word| history.iter().all(|g| g.matches(word)))
+                    .unwrap_or(self.tree.guess)
+            }
+        }
+    }
+}
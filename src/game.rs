@@ -0,0 +1,334 @@
+use crate::{nice_print, Correctness, Guess, Pattern, Word};
+use std::{collections::HashSet, fmt, time::Duration};
+
+mod absurdle;
+pub use absurdle::{worst_pattern, Absurdle};
+
+/// The number of guesses allowed in the real game.
+pub const DEFAULT_MAX_GUESSES: usize = 6;
+
+/// One guess of a game, as seen by the referee.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Turn {
+    pub guess: Word,
+    pub pattern: Pattern,
+    /// Number of dictionary words consistent with the patterns before this guess.
+    pub remaining_before: usize,
+    /// Number of dictionary words consistent with the patterns including this one.
+    pub remaining_after: usize,
+    /// Time the guesser took to come up with the guess.
+    pub elapsed: Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Solved,
+    /// The guesser ran out of guesses.
+    Failed,
+    /// The guesser came up with a guess that is not allowed.
+    Invalid(GuessError),
+}
+
+/// Everything that happened in a game.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameRecord {
+    pub answer: Word,
+    pub turns: Vec<Turn>,
+    pub outcome: Outcome,
+}
+
+impl GameRecord {
+    /// Number of guesses it took to find the answer, if it was found.
+    pub fn score(&self) -> Option<usize> {
+        match self.outcome {
+            Outcome::Solved => Some(self.turns.len()),
+            Outcome::Failed | Outcome::Invalid(_) => None,
+        }
+    }
+
+    /// Total time the guesser spent thinking.
+    pub fn elapsed(&self) -> Duration {
+        self.turns.iter().map(|turn| turn.elapsed).sum()
+    }
+}
+
+/// Everything that happened in a multi-board game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultiRecord {
+    pub answers: Vec<Word>,
+    pub guesses: Vec<Word>,
+    /// The number of guesses every board was solved with, if it was.
+    pub solved: Vec<Option<usize>>,
+}
+
+impl MultiRecord {
+    /// Number of guesses it took to solve every board, if they all were.
+    pub fn score(&self) -> Option<usize> {
+        self.solved
+            .iter()
+            .try_fold(0, |most, solved| solved.map(|n| most.max(n)))
+    }
+}
+
+/// Gets notified of the progress of a game while it is played.
+pub trait Observer {
+    fn turn(&mut self, _turn: &Turn) {}
+    fn finished(&mut self, _record: &GameRecord) {}
+}
+
+/// Observes nothing.
+impl Observer for () {}
+
+/// Prints every guess and the pattern it received.
+pub struct Printer;
+
+impl Observer for Printer {
+    fn turn(&mut self, turn: &Turn) {
+        if turn.pattern.is_solved() {
+            println!("Guessed '{}', which is the answer.", nice_print(turn.guess));
+        } else {
+            println!(
+                "Guessed '{}', received pattern: {}",
+                nice_print(turn.guess),
+                turn.pattern
+            );
+        }
+    }
+}
+
+/// Why a guess was rejected. A rejected guess doesn't use up a turn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuessError {
+    GameOver,
+    NotInDictionary(Word),
+    /// The word was already guessed (and wasn't the answer).
+    Repeated(Word),
+    /// Hard mode: a revealed green letter is not reused at its position.
+    MissingGreen {
+        position: usize,
+        letter: char,
+    },
+    /// Hard mode: a revealed letter (green or yellow) is not reused
+    /// as many times as it was revealed.
+    MissingLetter {
+        letter: char,
+        count: usize,
+    },
+}
+
+/// A single game, played one guess at a time, enforcing the rules.
+pub struct Game<'a> {
+    answer: Word,
+    dictionary: &'a HashSet<Word>,
+    history: Vec<Guess>,
+    hard: bool,
+    max_guesses: usize,
+    outcome: Option<Outcome>,
+}
+
+impl<'a> Game<'a> {
+    /// A game in normal mode with the usual 6 guesses.
+    /// The answer is always accepted as a guess, even if not in the dictionary.
+    pub fn new(answer: Word, dictionary: &'a HashSet<Word>) -> Self {
+        Self {
+            answer,
+            dictionary,
+            history: Vec::new(),
+            hard: false,
+            max_guesses: DEFAULT_MAX_GUESSES,
+            outcome: None,
+        }
+    }
+
+    /// In hard mode, every revealed hint must be used in subsequent guesses.
+    pub fn with_hard_mode(mut self, hard: bool) -> Self {
+        self.hard = hard;
+        self
+    }
+
+    pub fn with_max_guesses(mut self, max_guesses: usize) -> Self {
+        self.max_guesses = max_guesses;
+        self
+    }
+
+    pub fn submit(&mut self, word: Word) -> Result<Pattern, GuessError> {
+        if self.outcome.is_some() {
+            return Err(GuessError::GameOver);
+        }
+        if word != self.answer && !self.dictionary.contains(&word) {
+            return Err(GuessError::NotInDictionary(word));
+        }
+        if self.history.iter().any(|g| g.word == word) {
+            return Err(GuessError::Repeated(word));
+        }
+        if self.hard {
+            if let Some(e) = hard_mode_violation(&self.history, &word) {
+                return Err(e);
+            }
+        }
+
+        let pattern = Pattern::compute(&self.answer, &word);
+        self.history.push(Guess {
+            word,
+            mask: pattern,
+        });
+        if pattern.is_solved() {
+            self.outcome = Some(Outcome::Solved);
+        } else if self.history.len() >= self.max_guesses {
+            self.outcome = Some(Outcome::Failed);
+        }
+        Ok(pattern)
+    }
+
+    pub fn history(&self) -> &[Guess] {
+        &self.history
+    }
+
+    /// `None` while the game is still going on.
+    pub fn outcome(&self) -> Option<Outcome> {
+        self.outcome
+    }
+
+    pub fn guesses_left(&self) -> usize {
+        self.max_guesses.saturating_sub(self.history.len())
+    }
+}
+
+/// The first hint of `history` that `word` doesn't use, following the NYT hard mode rule:
+/// greens must be reused in place, and every revealed letter must be reused
+/// (as many times as it was revealed in a single guess).
+/// Grays don't constrain the next guesses.
+pub fn hard_mode_violation(history: &[Guess], word: &Word) -> Option<GuessError> {
+    for guess in history {
+        let mask = guess.mask.to_correctness();
+        for (i, &c) in mask.iter().enumerate() {
+            if c == Correctness::Correct && word[i] != guess.word[i] {
+                return Some(GuessError::MissingGreen {
+                    position: i,
+                    letter: guess.word[i] as char,
+                });
+            }
+        }
+        for (i, &c) in mask.iter().enumerate() {
+            if c == Correctness::Wrong {
+                continue;
+            }
+            let letter = guess.word[i];
+            let revealed = (0..5)
+                .filter(|&j| guess.word[j] == letter && mask[j] != Correctness::Wrong)
+                .count();
+            if (0..5).filter(|&j| word[j] == letter).count() < revealed {
+                return Some(GuessError::MissingLetter {
+                    letter: letter as char,
+                    count: revealed,
+                });
+            }
+        }
+    }
+    None
+}
+
+impl fmt::Display for GuessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::GameOver => write!(f, "the game is over"),
+            Self::NotInDictionary(word) => write!(f, "'{}' is not in the word list", word),
+            Self::Repeated(word) => write!(f, "'{}' was already guessed", word),
+            Self::MissingGreen { position, letter } => write!(
+                f,
+                "letter {} must be {}",
+                position + 1,
+                letter.to_ascii_uppercase()
+            ),
+            Self::MissingLetter { letter, count: 1 } => {
+                write!(f, "guess must contain {}", letter.to_ascii_uppercase())
+            }
+            Self::MissingLetter { letter, count } => write!(
+                f,
+                "guess must contain {} {} times",
+                letter.to_ascii_uppercase(),
+                count
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GuessError {}
+
+#[cfg(test)]
+mod tests {
+    use super::{Game, GuessError, Outcome};
+    use crate::{to_word, Pattern, Word};
+    use std::collections::HashSet;
+
+    fn dictionary() -> HashSet<Word> {
+        [
+            "right", "wrong", "wight", "tight", "girth", "fight", "thing", "might", "eight",
+        ]
+        .map(to_word)
+        .into()
+    }
+
+    #[test]
+    fn rejected_guesses_are_free() {
+        let dict = dictionary();
+        let mut game = Game::new(to_word("right"), &dict);
+        assert_eq!(
+            game.submit(to_word("abcde")),
+            Err(GuessError::NotInDictionary(to_word("abcde")))
+        );
+        assert!(game.submit(to_word("wrong")).is_ok());
+        assert_eq!(
+            game.submit(to_word("wrong")),
+            Err(GuessError::Repeated(to_word("wrong")))
+        );
+        assert_eq!(game.guesses_left(), 5);
+        assert_eq!(game.submit(to_word("right")), Ok(Pattern::SOLVED));
+        assert_eq!(game.outcome(), Some(Outcome::Solved));
+        assert_eq!(game.submit(to_word("right")), Err(GuessError::GameOver));
+    }
+
+    #[test]
+    fn loss() {
+        let dict = dictionary();
+        let mut game = Game::new(to_word("right"), &dict).with_max_guesses(2);
+        game.submit(to_word("wrong")).unwrap();
+        assert_eq!(game.outcome(), None);
+        game.submit(to_word("tight")).unwrap();
+        assert_eq!(game.outcome(), Some(Outcome::Failed));
+    }
+
+    #[test]
+    fn hard_mode() {
+        let dict = dictionary();
+        let mut game = Game::new(to_word("right"), &dict).with_hard_mode(true);
+        assert_eq!(game.submit(to_word("wight")), Ok("-####".parse().unwrap()));
+        assert_eq!(
+            game.submit(to_word("thing")),
+            Err(GuessError::MissingGreen {
+                position: 1,
+                letter: 'i'
+            })
+        );
+        assert!(game.submit(to_word("fight")).is_ok());
+
+        let mut game = Game::new(to_word("right"), &dict).with_hard_mode(true);
+        assert_eq!(game.submit(to_word("thing")), Ok("+++-+".parse().unwrap()));
+        assert_eq!(
+            game.submit(to_word("wrong")),
+            Err(GuessError::MissingLetter {
+                letter: 't',
+                count: 1
+            })
+        );
+        assert_eq!(game.submit(to_word("girth")), Ok("+#+++".parse().unwrap()));
+        assert_eq!(
+            game.submit(to_word("tight")),
+            Err(GuessError::MissingLetter {
+                letter: 'r',
+                count: 1
+            })
+        );
+        assert_eq!(game.submit(to_word("right")), Ok(Pattern::SOLVED));
+    }
+}
@@ -0,0 +1,133 @@
+use super::{GuessError, Outcome};
+use crate::{Correctness, Guess, Pattern, Word};
+use std::collections::HashSet;
+
+/// An adversarial game without a fixed answer, as in Absurdle:
+/// every guess is answered with the pattern keeping the most candidates,
+/// so the game only ends when the guess is the last candidate left.
+pub struct Absurdle<'a> {
+    /// The answers still consistent with every pattern given so far.
+    candidates: Vec<Word>,
+    dictionary: &'a HashSet<Word>,
+    history: Vec<Guess>,
+    max_guesses: usize,
+    outcome: Option<Outcome>,
+}
+
+impl<'a> Absurdle<'a> {
+    /// A game that could end on any of `candidates`, accepting the guesses of `dictionary`.
+    pub fn new(candidates: Vec<Word>, dictionary: &'a HashSet<Word>) -> Self {
+        assert!(
+            !candidates.is_empty(),
+            "There must be at least one candidate"
+        );
+        Self {
+            candidates,
+            dictionary,
+            history: Vec::new(),
+            max_guesses: usize::MAX,
+            outcome: None,
+        }
+    }
+
+    pub fn with_max_guesses(mut self, max_guesses: usize) -> Self {
+        self.max_guesses = max_guesses;
+        self
+    }
+
+    pub fn submit(&mut self, word: Word) -> Result<Pattern, GuessError> {
+        if self.outcome.is_some() {
+            return Err(GuessError::GameOver);
+        }
+        if !self.dictionary.contains(&word) && !self.candidates.contains(&word) {
+            return Err(GuessError::NotInDictionary(word));
+        }
+
+        let pattern = worst_pattern(&self.candidates, &word);
+        self.candidates
+            .retain(|candidate| Pattern::compute(candidate, &word) == pattern);
+        self.history.push(Guess {
+            word,
+            mask: pattern,
+        });
+        if pattern.is_solved() {
+            self.outcome = Some(Outcome::Solved);
+        } else if self.history.len() >= self.max_guesses {
+            self.outcome = Some(Outcome::Failed);
+        }
+        Ok(pattern)
+    }
+
+    /// The words the game could still end on.
+    pub fn candidates(&self) -> &[Word] {
+        &self.candidates
+    }
+
+    pub fn history(&self) -> &[Guess] {
+        &self.history
+    }
+
+    /// `None` while the game is still going on.
+    pub fn outcome(&self) -> Option<Outcome> {
+        self.outcome
+    }
+}
+
+/// The pattern of `guess` shared by the most `candidates`. Ties go to the pattern
+/// revealing the least: the fewest greens, then the fewest yellows.
+/// The all-green pattern is only given once no other one is left.
+pub fn worst_pattern(candidates: &[Word], guess: &Word) -> Pattern {
+    let mut sizes = [0usize; Pattern::COUNT];
+    for candidate in candidates {
+        sizes[Pattern::compute(candidate, guess).index()] += 1;
+    }
+    Pattern::all()
+        .filter(|p| sizes[p.index()] > 0)
+        .max_by_key(|&p| {
+            let tiles = p.to_correctness();
+            let count = |c| tiles.iter().filter(|&&t| t == c).count();
+            (
+                !p.is_solved(),
+                sizes[p.index()],
+                std::cmp::Reverse((count(Correctness::Correct), count(Correctness::Misplaced))),
+                std::cmp::Reverse(p.id()),
+            )
+        })
+        .expect("There is at least one candidate")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{worst_pattern, Absurdle};
+    use crate::{game::Outcome, to_word, Pattern, Word};
+    use std::collections::HashSet;
+
+    #[test]
+    fn dodges() {
+        let candidates: Vec<Word> = ["right", "fight", "tight", "cigar"].map(to_word).into();
+        let dictionary: HashSet<Word> = candidates.iter().copied().collect();
+        let mut game = Absurdle::new(candidates.clone(), &dictionary);
+
+        // "cigar" and "right" are alone, "fight" and "tight" share their pattern.
+        assert_eq!(
+            game.submit(to_word("cigar")),
+            Ok(Pattern::compute(&to_word("fight"), &to_word("cigar")))
+        );
+        assert_eq!(game.candidates().len(), 2);
+        // Both are alone, but the guess itself would end the game.
+        assert_eq!(
+            game.submit(to_word("fight")),
+            Ok(Pattern::compute(&to_word("tight"), &to_word("fight")))
+        );
+        assert_eq!(game.candidates(), [to_word("tight")]);
+        assert_eq!(game.submit(to_word("tight")), Ok(Pattern::SOLVED));
+        assert_eq!(game.outcome(), Some(Outcome::Solved));
+        assert_eq!(game.history().len(), 3);
+
+        // Only the answer is left.
+        assert_eq!(
+            worst_pattern(&[to_word("right")], &to_word("right")),
+            Pattern::SOLVED
+        );
+    }
+}
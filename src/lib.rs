@@ -1,33 +1,33 @@
-use ascii::{AsciiChar, ToAsciiChar};
 use colored::{ColoredString, Colorize};
-use std::collections::HashSet;
+use game::{Absurdle, Game, GameRecord, MultiRecord, Observer, Outcome, Turn};
+use std::{collections::HashSet, time::Instant};
 
 pub mod algorithms;
+pub mod game;
+pub mod matrix;
 pub mod modes;
-
-// TODO: Make Word a Struct instead of a type alias ======================
-// Traits should then be implemented for it, such as to be used in HashMap
-
-pub type Word = [AsciiChar; 5];
-
+mod pattern;
+pub use pattern::{ParsePatternError, Pattern};
+mod word;
+pub use word::{ParseWordError, Word};
+pub mod stats;
+pub mod tree;
+pub mod words;
+
+/// Shorthand for word literals. Panics if `slice` is not a valid word;
+/// use `str::parse` for anything coming from the outside.
 pub fn to_word(slice: &str) -> Word {
-    let word: [char; 5] = slice.chars().collect::<Vec<char>>().try_into().unwrap();
-    word.map(|c| c.to_ascii_char().unwrap())
+    slice.parse().unwrap()
 }
 
 pub fn nice_print(word: Word) -> ColoredString {
-    let mut printed = String::new();
-    for ch in word {
-        printed.push(ch.as_char());
-    }
-    printed.to_uppercase().purple().bold()
+    word.to_string().to_uppercase().purple().bold()
 }
 
-// =======================================================================
-
 const DICTIONARY: &str = include_str!("../dictionary.txt");
 pub const GAMES: &str = include_str!("../answers.txt");
-const MAX_GUESSES: usize = 100;
+/// The number of guesses allowed when evaluating solvers: practically unlimited.
+pub const MAX_GUESSES: usize = 100;
 
 pub const EPSILON: f64 = 0.0000000000001;
 
@@ -35,53 +35,151 @@ pub struct Wordle {
     dictionary: HashSet<Word>,
 }
 
+impl Default for Wordle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Wordle {
     pub fn new() -> Self {
         Self {
-            dictionary: HashSet::from_iter(DICTIONARY.lines().map(|line| {
-                let word: [char; 5] = line
-                    .split_once(' ')
-                    .expect("Every line must be of the format: word + space + frequency")
-                    .0
-                    .chars()
-                    .collect::<Vec<char>>()
-                    .try_into()
-                    .expect("Every word should consist of 5 characters");
-                word.map(|c| {
-                    c.to_ascii_char()
-                        .expect("Only ASCII characters are allowed")
-                })
-            })),
+            dictionary: words::WordLists::global().words().collect(),
         }
     }
 
-    pub fn play<G: Guesser>(&self, answer: &Word, guesser: &mut G) -> Option<usize> {
-        let mut history = Vec::new();
-        for i in 1..=MAX_GUESSES {
-            let guess = guesser.guess(&history);
-            if guess == *answer {
-                println!("Guessed '{}', which is the answer.", nice_print(guess));
-                return Some(i);
+    /// Plays a game without printing anything.
+    pub fn play<G: Guesser>(&self, answer: &Word, guesser: &mut G) -> GameRecord {
+        self.play_observed(answer, guesser, &mut ())
+    }
+
+    pub fn play_observed<G: Guesser, O: Observer>(
+        &self,
+        answer: &Word,
+        guesser: &mut G,
+        observer: &mut O,
+    ) -> GameRecord {
+        let mut game = Game::new(*answer, &self.dictionary).with_max_guesses(MAX_GUESSES);
+        let mut turns = Vec::new();
+        // Dictionary words that could still be the answer, to report on each turn.
+        let mut candidates: Vec<Word> = self.dictionary.iter().copied().collect();
+        let outcome = loop {
+            if let Some(outcome) = game.outcome() {
+                break outcome;
             }
 
-            assert!(self.dictionary.contains(&guess));
-            let correctness = Correctness::compute(answer, &guess);
-            println!(
-                "Guessed '{}', received pattern: {}",
-                nice_print(guess),
-                Correctness::to_string(&correctness)
-            );
-            history.push(Guess {
-                word: guess,
-                mask: correctness,
-            });
+            let start = Instant::now();
+            let guess = guesser.guess(game.history());
+            let elapsed = start.elapsed();
+
+            let pattern = match game.submit(guess) {
+                Ok(pattern) => pattern,
+                // The guesser would come up with the same guess again.
+                Err(e) => break Outcome::Invalid(e),
+            };
+            let remaining_before = candidates.len();
+            let last = game.history().last().unwrap();
+            candidates.retain(|word| last.matches(word));
+            let turn = Turn {
+                guess,
+                pattern,
+                remaining_before,
+                remaining_after: candidates.len(),
+                elapsed,
+            };
+            observer.turn(&turn);
+            turns.push(turn);
+        };
+
+        let record = GameRecord {
+            answer: *answer,
+            turns,
+            outcome,
+        };
+        observer.finished(&record);
+        record
+    }
+
+    /// Plays against the adversarial host of Absurdle, which can end on any of the answers.
+    /// The turns count the answers still possible for the host, and the answer of the record
+    /// is the one it was left with.
+    pub fn play_absurdle<G: Guesser, O: Observer>(
+        &self,
+        guesser: &mut G,
+        observer: &mut O,
+    ) -> GameRecord {
+        let answers = words::WordLists::global().answers.clone();
+        let mut game = Absurdle::new(answers, &self.dictionary).with_max_guesses(MAX_GUESSES);
+        let mut turns = Vec::new();
+        let outcome = loop {
+            if let Some(outcome) = game.outcome() {
+                break outcome;
+            }
+
+            let start = Instant::now();
+            let guess = guesser.guess(game.history());
+            let elapsed = start.elapsed();
+
+            let remaining_before = game.candidates().len();
+            let pattern = match game.submit(guess) {
+                Ok(pattern) => pattern,
+                Err(e) => break Outcome::Invalid(e),
+            };
+            let turn = Turn {
+                guess,
+                pattern,
+                remaining_before,
+                remaining_after: game.candidates().len(),
+                elapsed,
+            };
+            observer.turn(&turn);
+            turns.push(turn);
+        };
+
+        let record = GameRecord {
+            answer: game.candidates()[0],
+            turns,
+            outcome,
+        };
+        observer.finished(&record);
+        record
+    }
+
+    /// Plays a multi-board game, one board per answer, without printing anything.
+    /// Every guess is scored against every board not solved yet,
+    /// until they are all solved or a guess is rejected.
+    pub fn play_multi<G: MultiGuesser>(&self, answers: &[Word], guesser: &mut G) -> MultiRecord {
+        let mut games: Vec<Game> = answers
+            .iter()
+            .map(|answer| Game::new(*answer, &self.dictionary).with_max_guesses(MAX_GUESSES))
+            .collect();
+        let mut record = MultiRecord {
+            answers: answers.to_vec(),
+            guesses: Vec::new(),
+            solved: vec![None; answers.len()],
+        };
+        while record.guesses.len() < MAX_GUESSES && record.solved.contains(&None) {
+            let histories: Vec<Vec<Guess>> =
+                games.iter().map(|game| game.history().to_vec()).collect();
+            let guess = guesser.guess(&histories);
+            record.guesses.push(guess);
+            for (game, solved) in games.iter_mut().zip(&mut record.solved) {
+                if solved.is_some() {
+                    continue;
+                }
+                match game.submit(guess) {
+                    Ok(pattern) if pattern.is_solved() => *solved = Some(record.guesses.len()),
+                    Ok(_) => {}
+                    Err(_) => return record,
+                }
+            }
         }
-        None
+        record
     }
 }
 
 impl Correctness {
-    fn is_misplaced(letter: AsciiChar, answer: &Word, used: &mut [bool; 5]) -> bool {
+    fn is_misplaced(letter: u8, answer: &Word, used: &mut [bool; 5]) -> bool {
         // Because all the lengths are carried by the types,
         // the compiler should be able to eliminate all redundant bounds checks!
         for i in 0..5 {
@@ -122,31 +220,6 @@ impl Correctness {
 
         mask
     }
-
-    fn to_string(pattern: &[Self; 5]) -> String {
-        let mut res = String::with_capacity(5);
-        for m in pattern {
-            match m {
-                Correctness::Correct => res.push('🟩'),
-                Correctness::Misplaced => res.push('🟨'),
-                Correctness::Wrong => res.push('⬛'),
-            };
-        }
-        res
-    }
-
-    // generate all correctness patterns
-    #[inline(always)]
-    pub fn all_patterns() -> impl Iterator<Item = [Self; 5]> {
-        itertools::iproduct!(
-            [Self::Correct, Self::Misplaced, Self::Wrong],
-            [Self::Correct, Self::Misplaced, Self::Wrong],
-            [Self::Correct, Self::Misplaced, Self::Wrong],
-            [Self::Correct, Self::Misplaced, Self::Wrong],
-            [Self::Correct, Self::Misplaced, Self::Wrong]
-        )
-        .map(|(a, b, c, d, e)| [a, b, c, d, e])
-    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -159,41 +232,43 @@ pub enum Correctness {
     Correct,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Guess {
     pub word: Word,
-    pub mask: [Correctness; 5],
+    pub mask: Pattern,
 }
 
 impl Guess {
     pub fn matches(&self, other_word: &Word) -> bool {
         // // This one also works, but slower because it lacks short-circuiting:
-        // return Correctness::compute(other_word, self.word) == self.mask;
+        // return Pattern::compute(other_word, &self.word) == self.mask;
 
         // Because all the lengths are carried by the types,
         // the compiler should be able to eliminate all redundant bounds checks!
 
+        let mask = self.mask.to_correctness();
         let mut used: [bool; 5] = [false; 5];
 
         // Check green marks
         for i in 0..5 {
             if self.word[i] == other_word[i] {
-                if self.mask[i] != Correctness::Correct {
+                if mask[i] != Correctness::Correct {
                     return false;
                 }
                 used[i] = true;
-            } else if self.mask[i] == Correctness::Correct {
+            } else if mask[i] == Correctness::Correct {
                 return false;
             }
         }
 
         // Check yellow marks
-        for i in 0..5 {
-            if self.mask[i] == Correctness::Correct {
+        for (i, &c) in mask.iter().enumerate() {
+            if c == Correctness::Correct {
                 // Already checked for green mark
                 continue;
             }
             if Correctness::is_misplaced(self.word[i], other_word, &mut used)
-                != (self.mask[i] == Correctness::Misplaced)
+                != (c == Correctness::Misplaced)
             {
                 return false;
             }
@@ -208,6 +283,20 @@ pub trait Guesser {
     fn guess(&mut self, history: &[Guess]) -> Word;
 }
 
+/// Plays several boards at once, as in Dordle or Quordle: every guess is scored
+/// against the answer of every board that is not solved yet.
+pub trait MultiGuesser {
+    /// `histories[b]` is the history of board `b`. Once solved,
+    /// it ends with the all-green pattern and doesn't get the later guesses.
+    fn guess(&mut self, histories: &[Vec<Guess>]) -> Word;
+}
+
+impl<G: Guesser + ?Sized> Guesser for Box<G> {
+    fn guess(&mut self, history: &[Guess]) -> Word {
+        (**self).guess(history)
+    }
+}
+
 impl Guesser for fn(history: &[Guess]) -> Word {
     fn guess(&mut self, history: &[Guess]) -> Word {
         (*self)(history)
@@ -240,13 +329,19 @@ macro_rules! guesser {
 #[cfg(test)]
 mod tests {
     mod game {
-        use crate::{to_word, Guess, Wordle};
+        use crate::{
+            game::{GuessError, Outcome},
+            to_word, Guess, Wordle,
+        };
+
+        // Guessing the same wrong word twice is not allowed.
+        const WRONG: [&str; 5] = ["wrong", "bread", "cloth", "dumpy", "fjord"];
 
         #[test]
         fn genius() {
             let w = Wordle::new();
             let mut guesser = guesser!(|_history| { to_word("right") });
-            assert_eq!(w.play(&to_word("right"), &mut guesser), Some(1));
+            assert_eq!(w.play(&to_word("right"), &mut guesser).score(), Some(1));
         }
         #[test]
         fn magnificent() {
@@ -255,9 +350,9 @@ mod tests {
                 if history.len() == 1 {
                     return to_word("right");
                 }
-                to_word("wrong")
+                to_word(WRONG[history.len()])
             });
-            assert_eq!(w.play(&to_word("right"), &mut guesser), Some(2));
+            assert_eq!(w.play(&to_word("right"), &mut guesser).score(), Some(2));
         }
         #[test]
         fn impressive() {
@@ -266,9 +361,9 @@ mod tests {
                 if history.len() == 2 {
                     return to_word("right");
                 }
-                to_word("wrong")
+                to_word(WRONG[history.len()])
             });
-            assert_eq!(w.play(&to_word("right"), &mut guesser), Some(3));
+            assert_eq!(w.play(&to_word("right"), &mut guesser).score(), Some(3));
         }
         #[test]
         fn splendid() {
@@ -277,9 +372,9 @@ mod tests {
                 if history.len() == 3 {
                     return to_word("right");
                 }
-                to_word("wrong")
+                to_word(WRONG[history.len()])
             });
-            assert_eq!(w.play(&to_word("right"), &mut guesser), Some(4));
+            assert_eq!(w.play(&to_word("right"), &mut guesser).score(), Some(4));
         }
         #[test]
         fn great() {
@@ -288,9 +383,9 @@ mod tests {
                 if history.len() == 4 {
                     return to_word("right");
                 }
-                to_word("wrong")
+                to_word(WRONG[history.len()])
             });
-            assert_eq!(w.play(&to_word("right"), &mut guesser), Some(5));
+            assert_eq!(w.play(&to_word("right"), &mut guesser).score(), Some(5));
         }
         #[test]
         fn phew() {
@@ -299,15 +394,83 @@ mod tests {
                 if history.len() == 5 {
                     return to_word("right");
                 }
-                to_word("wrong")
+                to_word(WRONG[history.len()])
+            });
+            assert_eq!(w.play(&to_word("right"), &mut guesser).score(), Some(6));
+        }
+        #[test]
+        fn record() {
+            let w = Wordle::new();
+            let mut guesser = guesser!(|history| {
+                if history.len() == 1 {
+                    return to_word("right");
+                }
+                to_word("wight")
             });
-            assert_eq!(w.play(&to_word("right"), &mut guesser), Some(6));
+            let record = w.play(&to_word("right"), &mut guesser);
+            assert_eq!(record.outcome, Outcome::Solved);
+            assert_eq!(record.turns.len(), 2);
+            assert_eq!(record.turns[0].guess, to_word("wight"));
+            assert_eq!(record.turns[0].pattern, mask!(W C C C C).into());
+            assert_eq!(record.turns[0].remaining_before, 12947);
+            // ?ight, except wight
+            assert_eq!(record.turns[0].remaining_after, 14);
+            assert_eq!(record.turns[1].pattern, crate::Pattern::SOLVED);
+            assert_eq!(record.turns[1].remaining_before, 14);
+            assert_eq!(record.turns[1].remaining_after, 1);
         }
         #[test]
         fn oops() {
+            let w = Wordle::new();
+            // A new wrong word every time, until there are no guesses left.
+            let mut guesser = guesser!(|history| {
+                crate::words::WordLists::global()
+                    .words()
+                    .filter(|&word| word != to_word("right"))
+                    .nth(history.len())
+                    .unwrap()
+            });
+            let record = w.play(&to_word("right"), &mut guesser);
+            assert_eq!(record.score(), None);
+            assert_eq!(record.outcome, Outcome::Failed);
+            assert_eq!(record.turns.len(), crate::MAX_GUESSES);
+        }
+        #[test]
+        fn multi() {
+            struct G(&'static [&'static str]);
+            impl crate::MultiGuesser for G {
+                fn guess(&mut self, histories: &[Vec<Guess>]) -> crate::Word {
+                    // The solved boards don't get the later guesses.
+                    let turn = histories.iter().map(Vec::len).max().unwrap();
+                    assert!(histories
+                        .iter()
+                        .all(|h| h.len() == turn || h.last().is_some_and(|g| g.mask.is_solved())));
+                    to_word(self.0[turn])
+                }
+            }
+            let w = Wordle::new();
+            let answers = ["right", "fight", "tight"].map(to_word);
+            let record = w.play_multi(&answers, &mut G(&["fight", "wrong", "right", "tight"]));
+            assert_eq!(record.solved, [Some(3), Some(1), Some(4)]);
+            assert_eq!(record.score(), Some(4));
+            assert_eq!(record.guesses.len(), 4);
+
+            // The second "wrong" is rejected on the board that isn't solved.
+            let answers = ["right", "wrong"].map(to_word);
+            let record = w.play_multi(&answers, &mut G(&["wrong", "wrong"]));
+            assert_eq!(record.solved, [None, Some(1)]);
+            assert_eq!(record.score(), None);
+        }
+        #[test]
+        fn stubborn() {
             let w = Wordle::new();
             let mut guesser = guesser!(|_history| { to_word("wrong") });
-            assert_eq!(w.play(&to_word("right"), &mut guesser), None);
+            let record = w.play(&to_word("right"), &mut guesser);
+            assert_eq!(record.score(), None);
+            assert_eq!(
+                record.outcome,
+                Outcome::Invalid(GuessError::Repeated(to_word("wrong")))
+            );
         }
     }
 
@@ -390,7 +553,7 @@ mod tests {
             ($prev:literal + [$($mask:tt)+] allows $next:literal) => {
                 assert!(Guess {
                     word: to_word($prev),
-                    mask: mask![$($mask )+]
+                    mask: mask![$($mask )+].into()
                 }
                 .matches(&to_word($next)));
                 assert_eq!($crate::Correctness::compute(&to_word($next), &to_word($prev)), mask![$($mask )+]);
@@ -398,7 +561,7 @@ mod tests {
             ($prev:literal + [$($mask:tt)+] disallows $next:literal) => {
                 assert!(!Guess {
                     word: to_word($prev),
-                    mask: mask![$($mask )+]
+                    mask: mask![$($mask )+].into()
                 }
                 .matches(&to_word($next)));
                 assert_ne!($crate::Correctness::compute(&to_word($next), &to_word($prev)), mask![$($mask )+]);
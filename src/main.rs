@@ -1,5 +1,20 @@
 use clap::{Parser, ValueEnum};
-use rogerthat::modes::{interactive, run_all};
+use rogerthat::algorithms::{
+    Cached, Frequency, HardMode, Interactive, MaskBuckets, Memo, Memoized, Minimax, MultiBoard,
+    Naive, Optimal, Options, Sigmoid, Strategy, TreeGuesser,
+};
+use rogerthat::matrix::PatternMatrix;
+use rogerthat::modes::{
+    absurdle, absurdle_solver, build_tree, compare, interactive, multi_interactive, multi_run_all,
+    protocol::Address, run_all, serve, solve,
+};
+use rogerthat::stats::Stats;
+use rogerthat::tree::Tree;
+use rogerthat::words::WordLists;
+use rogerthat::Guesser;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -15,6 +30,111 @@ struct Cli {
 
     #[clap(short, long)]
     skipped_rounds: Option<usize>,
+
+    /// Number of games played at the same time, each by its own solver
+    /// (and its own connection in solve mode).
+    #[clap(short = 'j', long)]
+    threads: Option<usize>,
+
+    /// Number of boards played at once, each with its own answer, as in Dordle (2),
+    /// Quordle (4) or Octordle (8). Only in run-all and interactive modes, with the multi-board solver.
+    #[clap(long)]
+    boards: Option<usize>,
+
+    /// Hard mode of the solver. In serve mode, any hard mode makes the games enforce the NYT rule.
+    #[arg(long, value_enum)]
+    hard: Option<Hard>,
+
+    /// How likely the solver thinks every word is to be the answer.
+    #[arg(long, value_enum)]
+    prior: Option<PriorKind>,
+
+    /// What the solver optimizes.
+    #[arg(long, value_enum)]
+    strategy: Option<StrategyKind>,
+
+    /// Re-rank this many of the most informative guesses by how much they
+    /// and the best guess after them tell together. Only with the information strategy
+    /// in normal mode, and only in the solvers using the pattern matrix (mask-buckets, memoized, interactive).
+    #[arg(long)]
+    lookahead: Option<usize>,
+
+    /// Print what the solver thinks while guessing, like the number of words still possible.
+    /// Always on in interactive mode, and off with several threads.
+    #[clap(long)]
+    verbose: bool,
+
+    /// Frequency rank at which the sigmoid prior is 1/2.
+    #[arg(long, default_value_t = Sigmoid::default().midpoint, value_parser = finite)]
+    sigmoid_midpoint: f64,
+
+    /// Number of ranks over which the sigmoid prior goes from likely to unlikely.
+    #[arg(long, default_value_t = Sigmoid::default().width, value_parser = positive)]
+    sigmoid_width: f64,
+
+    /// Allowed guesses, one per line: word + space + frequency.
+    /// Defaults to the built-in dictionary.
+    #[clap(long)]
+    dictionary: Option<PathBuf>,
+
+    /// Answers to play, one word per line. Defaults to the built-in list.
+    #[clap(long)]
+    answers: Option<PathBuf>,
+
+    /// File caching the precomputed guess/answer pattern matrix.
+    /// It is created if missing (or stale) and memory-mapped otherwise.
+    #[clap(long)]
+    pattern_matrix: Option<PathBuf>,
+
+    /// File keeping the guesses of the memoized and interactive solvers across runs.
+    /// It is created if missing and updated at the end of the run.
+    #[clap(long)]
+    memo: Option<PathBuf>,
+
+    /// Where serve mode listens and solve mode connects to:
+    /// a localhost TCP address or the path of a Unix socket.
+    #[clap(long, default_value = "127.0.0.1:7878")]
+    address: Address,
+
+    /// Number of guesses allowed in the games hosted in serve mode.
+    #[clap(long, default_value_t = rogerthat::MAX_GUESSES)]
+    max_guesses: usize,
+
+    /// Games of run-all mode taking more guesses than this count as failures in the report.
+    #[clap(long, default_value_t = rogerthat::game::DEFAULT_MAX_GUESSES)]
+    limit: usize,
+
+    /// Number of best guesses interactive mode lists with every suggestion,
+    /// with their expected information and number of remaining words. Changed with `TOP n`.
+    #[clap(long, default_value_t = 0)]
+    top: usize,
+
+    /// File interactive mode resumes the session from, if it exists, and keeps it in as it goes.
+    #[clap(long)]
+    session: Option<PathBuf>,
+
+    /// File run-all mode writes the report with every game to, as CSV (`.csv`) or JSON (any other extension).
+    #[clap(long)]
+    report: Option<PathBuf>,
+
+    /// The other solver of compare mode, given by its flags, as in
+    /// `--against "-i mask-buckets --strategy prudent"`.
+    /// The word lists and the pattern matrix are the ones of the first solver.
+    #[arg(long, allow_hyphen_values = true)]
+    against: Option<String>,
+
+    /// Worst-case number of guesses the optimal solver must find every answer within.
+    #[clap(long)]
+    worst_case: Option<usize>,
+
+    /// Strategy tree file played from by the tree solver.
+    #[clap(long)]
+    tree: Option<PathBuf>,
+
+    /// File tree mode writes the strategy tree to: as JSON, as a Graphviz DOT graph,
+    /// or in the compact binary format, depending on the extension (`.json`, `.dot` or any other).
+    #[clap(long)]
+    tree_out: Option<PathBuf>,
 }
 
 #[derive(Parser, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
@@ -24,45 +144,328 @@ enum Implementation {
     MaskBuckets,
     Memoized,
     Interactive,
+    /// Exhaustive search of the strategy with the fewest guesses on average over the answers.
+    /// Only practical for small answer lists.
+    Optimal,
+    /// Play from the strategy tree in --tree.
+    Tree,
+    /// Minimize the number of guesses in the worst case, as against the Absurdle host.
+    Minimax,
+}
+
+#[derive(Parser, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum Hard {
+    Off,
+    /// Revealed hints must be used, as in the NYT game.
+    Nyt,
+    /// Only guess words that could be the answer.
+    Strict,
+}
+
+#[derive(Parser, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum PriorKind {
+    /// Proportional to the frequency of the word.
+    Frequency,
+    /// Sigmoid of the frequency rank of the word.
+    Sigmoid,
+}
+
+#[derive(Parser, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum StrategyKind {
+    /// Maximize the expected information.
+    Information,
+    /// Minimize the expected number of guesses, optimistically.
+    Optimistic,
+    /// Minimize the expected number of guesses, prudently.
+    Prudent,
 }
 
 #[derive(Parser, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
 enum Mode {
     RunAll,
     Interactive,
+    /// Host the games for solvers connecting to --address.
+    Serve,
+    /// Play the games hosted at --address.
+    Solve,
+    /// Save the strategy tree of the solver on the answers to --tree-out.
+    Tree,
+    /// Show the games where the solver and the one of --against play differently.
+    Compare,
+    /// Play against a host that dodges the guesses, as in Absurdle:
+    /// yourself, or the solver given with --implementation.
+    Absurdle,
+    /// Interactive mode in a full-screen interface.
+    #[cfg(feature = "tui")]
+    Tui,
 }
 
 fn main() {
     let cli = Cli::parse();
 
+    if cli.dictionary.is_some() || cli.answers.is_some() {
+        match WordLists::load(cli.dictionary.as_deref(), cli.answers.as_deref()) {
+            Ok(lists) => {
+                // Nothing has used the word lists yet.
+                let _ = lists.install();
+            }
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    if let Some(path) = &cli.pattern_matrix {
+        if let Err(e) = PatternMatrix::init_global(path) {
+            eprintln!(
+                "Error: cannot use pattern matrix file {}: {}",
+                path.display(),
+                e
+            );
+            std::process::exit(1);
+        }
+    }
+
+    let options = solver_options(&cli);
+
+    let memo = Arc::new(Mutex::new(match &cli.memo {
+        Some(path) if path.exists() => match Memo::load(path, &options) {
+            Ok(memo) => memo,
+            Err(e) => {
+                eprintln!("Error: cannot use memo file {}: {}", path.display(), e);
+                std::process::exit(1);
+            }
+        },
+        _ => Memo::new(&options),
+    }));
+
+    if let Some(boards) = cli.boards {
+        match cli.mode {
+            _ if boards == 0 => eprintln!("Error: there must be at least one board"),
+            _ if options.hard_mode != HardMode::Off => {
+                eprintln!("Error: there is no hard mode with several boards")
+            }
+            Some(Mode::Interactive) => {
+                multi_interactive(MultiBoard::with_options(boards, options));
+                return;
+            }
+            Some(Mode::RunAll) | None => {
+                multi_run_all(
+                    MultiBoard::with_options(boards, options),
+                    boards,
+                    cli.num_rounds,
+                    cli.skipped_rounds,
+                );
+                return;
+            }
+            Some(_) => {
+                eprintln!("Error: several boards only work in run-all and interactive modes")
+            }
+        }
+        std::process::exit(1);
+    }
+
+    let result = match cli.mode {
+        Some(Mode::Interactive) => {
+            interactive(
+                || Interactive::with_memo(options.clone(), Arc::clone(&memo)),
+                cli.top,
+                cli.session.as_deref(),
+            );
+            Ok(())
+        }
+        #[cfg(feature = "tui")]
+        Some(Mode::Tui) => rogerthat::modes::tui(
+            || Interactive::with_memo(options.clone(), Arc::clone(&memo)),
+            cli.top,
+            cli.session.as_deref(),
+        ),
+        Some(Mode::Serve) => serve(
+            &cli.address,
+            cli.num_rounds,
+            cli.skipped_rounds,
+            options.hard_mode != HardMode::Off,
+            cli.max_guesses,
+        ),
+        Some(Mode::Absurdle) if cli.implementation.is_none() => {
+            absurdle();
+            Ok(())
+        }
+        Some(Mode::Compare) => match &cli.against {
+            Some(args) => {
+                // The solver flags of the other configuration, parsed like ours.
+                let other =
+                    Cli::parse_from(std::iter::once("rogerthat").chain(args.split_whitespace()));
+                let other_options = solver_options(&other);
+                let other_memo = Arc::new(Mutex::new(Memo::new(&other_options)));
+                compare(
+                    solver(&cli, options.clone(), Arc::clone(&memo))(),
+                    solver(&other, other_options, other_memo)(),
+                    cli.num_rounds,
+                    cli.skipped_rounds,
+                );
+                Ok(())
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "compare mode needs --against \"SOLVER FLAGS\"",
+            )),
+        },
+        Some(Mode::RunAll) | Some(Mode::Solve) | Some(Mode::Tree) | Some(Mode::Absurdle) | None => {
+            play(&cli, solver(&cli, options.clone(), Arc::clone(&memo)))
+        }
+    };
+
+    if let Some(path) = &cli.memo {
+        if let Err(e) = memo.lock().unwrap().save(path) {
+            eprintln!("Error: cannot save memo file {}: {}", path.display(), e);
+            std::process::exit(1);
+        }
+    }
+
+    if let Err(e) = result {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+/// Parses a flag that must be a number, not infinite or NaN.
+fn finite(arg: &str) -> Result<f64, String> {
+    match arg.parse::<f64>() {
+        Ok(x) if x.is_finite() => Ok(x),
+        Ok(_) => Err("must be a finite number".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Parses a flag that must be a number greater than 0.
+fn positive(arg: &str) -> Result<f64, String> {
+    match finite(arg)? {
+        x if x > 0.0 => Ok(x),
+        _ => Err("must be greater than 0".to_string()),
+    }
+}
+
+/// The options of the solvers, from the flags.
+fn solver_options(cli: &Cli) -> Options {
+    let options = Options {
+        hard_mode: match cli.hard {
+            Some(Hard::Off) | None => HardMode::Off,
+            Some(Hard::Nyt) => HardMode::Nyt,
+            Some(Hard::Strict) => HardMode::Strict,
+        },
+        prior: match cli.prior {
+            Some(PriorKind::Frequency) | None => Arc::new(Frequency),
+            Some(PriorKind::Sigmoid) => Arc::new(Sigmoid {
+                midpoint: cli.sigmoid_midpoint,
+                width: cli.sigmoid_width,
+            }),
+        },
+        strategy: match cli.strategy {
+            Some(StrategyKind::Information) | None => Strategy::Information,
+            Some(StrategyKind::Optimistic) => Strategy::Optimistic,
+            Some(StrategyKind::Prudent) => Strategy::Prudent,
+        },
+        lookahead: cli.lookahead,
+        // Somebody playing elsewhere wants to know how it is going.
+        // Games played in parallel would mix up what their solvers print.
+        verbose: (cli.verbose && cli.threads.unwrap_or(1) <= 1)
+            || cli.mode == Some(Mode::Interactive),
+    };
+
+    if cli.verbose && cli.threads.unwrap_or(1) > 1 {
+        eprintln!("Warning: --verbose is ignored when games are played in parallel");
+    }
+
+    if options.lookahead.is_some() && options.strategy != Strategy::Information {
+        eprintln!("Error: --lookahead only works with the information strategy");
+        std::process::exit(1);
+    }
+    if options.lookahead.is_some() && options.hard_mode != HardMode::Off {
+        // The follow-ups would have to be allowed after every pattern of the first guess.
+        eprintln!("Error: --lookahead only works in normal mode");
+        std::process::exit(1);
+    }
+
+    options
+}
+
+/// Makes the solvers given by the flags: every call gives a new one.
+fn solver(
+    cli: &Cli,
+    options: Options,
+    memo: Arc<Mutex<Memo>>,
+) -> Box<dyn Fn() -> Box<dyn Guesser> + Sync> {
+    match cli.implementation {
+        Some(Implementation::Naive) => {
+            Box::new(move || Box::new(Naive::with_options(options.clone())))
+        }
+        Some(Implementation::Cached) => {
+            Box::new(move || Box::new(Cached::with_options(options.clone())))
+        }
+        Some(Implementation::MaskBuckets) => {
+            Box::new(move || Box::new(MaskBuckets::with_options(options.clone())))
+        }
+        Some(Implementation::Memoized) | None => {
+            Box::new(move || Box::new(Memoized::with_memo(options.clone(), Arc::clone(&memo))))
+        }
+        Some(Implementation::Interactive) => {
+            Box::new(move || Box::new(Interactive::with_memo(options.clone(), Arc::clone(&memo))))
+        }
+        Some(Implementation::Optimal) => {
+            let worst_case = cli.worst_case;
+            Box::new(move || {
+                let optimal = Optimal::new().with_verbose(options.verbose);
+                Box::new(match worst_case {
+                    Some(guesses) => optimal.with_worst_case(guesses),
+                    None => optimal,
+                })
+            })
+        }
+        Some(Implementation::Minimax) => {
+            Box::new(move || Box::new(Minimax::new().with_verbose(options.verbose)))
+        }
+        Some(Implementation::Tree) => {
+            let tree = match cli.tree.as_deref().map(Tree::load) {
+                Some(Ok(tree)) => tree,
+                Some(Err(e)) => {
+                    eprintln!("Error: cannot use tree file: {}", e);
+                    std::process::exit(1);
+                }
+                None => {
+                    eprintln!("Error: the tree solver needs --tree FILE");
+                    std::process::exit(1);
+                }
+            };
+            Box::new(move || Box::new(TreeGuesser::new(tree.clone()).with_verbose(options.verbose)))
+        }
+    }
+}
+
+/// Runs the modes that play with any solver.
+fn play<G: Guesser>(cli: &Cli, mk: impl Fn() -> G + Sync) -> io::Result<()> {
     match cli.mode {
-        Some(Mode::Interactive) => interactive(),
-        Some(Mode::RunAll) | None => match cli.implementation {
-            Some(Implementation::Naive) => run_all(
-                || rogerthat::algorithms::Naive::new(),
-                cli.num_rounds,
-                cli.skipped_rounds,
-            ),
-            Some(Implementation::Cached) => run_all(
-                || rogerthat::algorithms::Cached::new(),
-                cli.num_rounds,
-                cli.skipped_rounds,
-            ),
-            Some(Implementation::MaskBuckets) => run_all(
-                || rogerthat::algorithms::MaskBuckets::new(),
-                cli.num_rounds,
-                cli.skipped_rounds,
-            ),
-            Some(Implementation::Memoized) | None => run_all(
-                || rogerthat::algorithms::Memoized::new(),
-                cli.num_rounds,
-                cli.skipped_rounds,
-            ),
-            Some(Implementation::Interactive) => run_all(
-                || rogerthat::algorithms::Interactive::new(),
-                cli.num_rounds,
-                cli.skipped_rounds,
-            ),
+        Some(Mode::Solve) => solve(mk, &cli.address, cli.threads),
+        Some(Mode::Tree) => match &cli.tree_out {
+            Some(path) => build_tree((mk)(), cli.num_rounds, cli.skipped_rounds, path),
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "tree mode needs --tree-out FILE",
+            )),
         },
+        Some(Mode::Absurdle) => {
+            absurdle_solver((mk)());
+            Ok(())
+        }
+        _ => {
+            let records = run_all(mk, cli.num_rounds, cli.skipped_rounds, cli.threads);
+            let stats = Stats::new(&records, cli.limit);
+            println!("{}", stats);
+            match &cli.report {
+                Some(path) => stats.save(path),
+                None => Ok(()),
+            }
+        }
     }
 }
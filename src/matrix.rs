@@ -0,0 +1,264 @@
+use crate::{words::WordLists, Guess, Pattern, Word};
+use memmap2::Mmap;
+use once_cell::sync::OnceCell;
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+const MAGIC: &[u8; 8] = b"RGTMTRX1";
+
+static GLOBAL: OnceCell<PatternMatrix> = OnceCell::new();
+
+enum Data {
+    Owned(Vec<u8>),
+    Mapped { mmap: Mmap, offset: usize },
+}
+
+/// The pattern received for every (guess, answer) pair of a word list,
+/// computed once and then only looked up.
+///
+/// Row `g` holds the pattern ids (see `Pattern::id`) of guess `words[g]` against every answer,
+/// so the hot loops of the solvers only ever touch one row at a time.
+pub struct PatternMatrix {
+    words: Vec<Word>,
+    index: HashMap<Word, usize>,
+    data: Data,
+}
+
+impl PatternMatrix {
+    pub fn compute(words: Vec<Word>) -> Self {
+        let n = words.len();
+        let mut data = vec![0u8; n * n];
+
+        // Every row is independent, so spread them over all cores.
+        let threads = std::thread::available_parallelism().map_or(1, |t| t.get());
+        let rows_per_chunk = n.div_ceil(threads).max(1);
+        std::thread::scope(|s| {
+            for (chunk_idx, chunk) in data.chunks_mut(rows_per_chunk * n.max(1)).enumerate() {
+                let words = &words;
+                s.spawn(move || {
+                    for (i, row) in chunk.chunks_mut(n).enumerate() {
+                        let guess = &words[chunk_idx * rows_per_chunk + i];
+                        for (cell, answer) in row.iter_mut().zip(words) {
+                            *cell = Pattern::compute(answer, guess).id();
+                        }
+                    }
+                });
+            }
+        });
+
+        Self::with_data(words, Data::Owned(data))
+    }
+
+    /// Memory-maps a matrix previously written by `save`.
+    /// Fails if the file was built for a different word list.
+    pub fn load(path: &Path, words: Vec<Word>) -> io::Result<Self> {
+        let file = File::open(path)?;
+        // Safety: the file is only read, and we never hand out references
+        // that outlive the matrix. Modifying it while mapped is on the user.
+        let mmap = unsafe { Mmap::map(&file)? };
+
+        let n = words.len();
+        let offset = MAGIC.len() + 4 + 5 * n;
+        let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg.to_string());
+        if mmap.len() < MAGIC.len() + 4 || &mmap[..MAGIC.len()] != MAGIC {
+            return Err(invalid("not a pattern matrix file"));
+        }
+        let stored_n = u32::from_le_bytes(mmap[MAGIC.len()..MAGIC.len() + 4].try_into().unwrap());
+        if stored_n as usize != n
+            || mmap.len() != offset + n * n
+            || !mmap[MAGIC.len() + 4..offset]
+                .chunks(5)
+                .zip(&words)
+                .all(|(stored, word)| stored == word.as_bytes())
+        {
+            return Err(invalid(
+                "pattern matrix was built for a different word list",
+            ));
+        }
+        // Not to panic on a pattern id out of range in the middle of a game.
+        if !mmap[offset..]
+            .iter()
+            .all(|&id| (id as usize) < Pattern::COUNT)
+        {
+            return Err(invalid("pattern matrix file is corrupted"));
+        }
+
+        Ok(Self::with_data(words, Data::Mapped { mmap, offset }))
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        out.write_all(MAGIC)?;
+        out.write_all(&(self.words.len() as u32).to_le_bytes())?;
+        for word in &self.words {
+            out.write_all(word.as_bytes())?;
+        }
+        out.write_all(self.data())?;
+        out.flush()
+    }
+
+    /// Loads the matrix from `path` if it is up to date,
+    /// otherwise computes it and writes it there for next time.
+    pub fn load_or_compute(path: &Path, words: Vec<Word>) -> io::Result<Self> {
+        match Self::load(path, words.clone()) {
+            Ok(matrix) => Ok(matrix),
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::NotFound | io::ErrorKind::InvalidData
+                ) =>
+            {
+                let matrix = Self::compute(words);
+                matrix.save(path)?;
+                Ok(matrix)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// The matrix over the whole dictionary (see `WordLists::global`), shared by all solvers.
+    /// Computed on first use unless `init_global` was called before.
+    pub fn global() -> &'static Self {
+        GLOBAL.get_or_init(|| Self::compute(dictionary_words()))
+    }
+
+    /// Backs the shared matrix by the file at `path` (see `load_or_compute`).
+    /// Must be called before the first call to `global`.
+    pub fn init_global(path: &Path) -> io::Result<&'static Self> {
+        let matrix = Self::load_or_compute(path, dictionary_words())?;
+        GLOBAL.set(matrix).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                "pattern matrix is already initialized",
+            )
+        })?;
+        Ok(GLOBAL.get().unwrap())
+    }
+
+    pub fn words(&self) -> &[Word] {
+        &self.words
+    }
+
+    pub fn index_of(&self, word: &Word) -> Option<usize> {
+        self.index.get(word).copied()
+    }
+
+    /// Pattern ids of guess number `guess` against every answer.
+    #[inline(always)]
+    pub fn row(&self, guess: usize) -> &[u8] {
+        let n = self.words.len();
+        &self.data()[guess * n..(guess + 1) * n]
+    }
+
+    #[inline(always)]
+    pub fn get(&self, guess: usize, answer: usize) -> Pattern {
+        Pattern::from_id(self.row(guess)[answer]).unwrap()
+    }
+
+    /// Pattern for any pair of words,
+    /// falling back to computing it if either is not in the matrix.
+    pub fn pattern(&self, guess: &Word, answer: &Word) -> Pattern {
+        match (self.index_of(guess), self.index_of(answer)) {
+            (Some(g), Some(a)) => self.get(g, a),
+            _ => Pattern::compute(answer, guess),
+        }
+    }
+
+    /// Keeps only the words that would have produced the pattern of `guess`.
+    pub fn retain_matching<V>(&self, remaining: &mut HashMap<Word, V>, guess: &Guess) {
+        let pattern = guess.mask.id();
+        match self.index_of(&guess.word) {
+            Some(g) => {
+                let row = self.row(g);
+                remaining.retain(|word, _| match self.index_of(word) {
+                    Some(a) => row[a] == pattern,
+                    None => guess.matches(word),
+                });
+            }
+            None => remaining.retain(|word, _| guess.matches(word)),
+        }
+    }
+
+    fn with_data(words: Vec<Word>, data: Data) -> Self {
+        let index = words.iter().enumerate().map(|(i, &w)| (w, i)).collect();
+        Self { words, index, data }
+    }
+
+    fn data(&self) -> &[u8] {
+        match &self.data {
+            Data::Owned(data) => data,
+            Data::Mapped { mmap, offset } => &mmap[*offset..],
+        }
+    }
+}
+
+fn dictionary_words() -> Vec<Word> {
+    WordLists::global().words().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PatternMatrix;
+    use crate::{to_word, Correctness::*, Guess, Pattern};
+    use std::collections::HashMap;
+
+    fn words() -> Vec<crate::Word> {
+        [
+            "tares", "right", "wrong", "aabbb", "ccaac", "caacc", "abcde",
+        ]
+        .map(to_word)
+        .to_vec()
+    }
+
+    #[test]
+    fn matches_compute() {
+        let matrix = PatternMatrix::compute(words());
+        for (g, guess) in words().iter().enumerate() {
+            for (a, answer) in words().iter().enumerate() {
+                assert_eq!(matrix.get(g, a), Pattern::compute(answer, guess));
+            }
+        }
+    }
+
+    #[test]
+    fn retain() {
+        let matrix = PatternMatrix::compute(words());
+        let guess = Guess {
+            word: to_word("ccaac"),
+            mask: [Wrong, Wrong, Misplaced, Misplaced, Wrong].into(),
+        };
+        let mut remaining: HashMap<_, _> = words().into_iter().map(|w| (w, 1)).collect();
+        // Not in the matrix, must still be filtered correctly.
+        remaining.insert(to_word("aabbd"), 1);
+        matrix.retain_matching(&mut remaining, &guess);
+        let mut left: Vec<_> = remaining.into_keys().collect();
+        left.sort();
+        assert_eq!(left, vec![to_word("aabbb"), to_word("aabbd")]);
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path = std::env::temp_dir().join(format!("rogerthat-matrix-{}", std::process::id()));
+        let matrix = PatternMatrix::compute(words());
+        matrix.save(&path).unwrap();
+
+        let loaded = PatternMatrix::load(&path, words()).unwrap();
+        assert_eq!(loaded.data(), matrix.data());
+        assert!(PatternMatrix::load(&path, words()[1..].to_vec()).is_err());
+
+        // A damaged file is refused, and made again.
+        let mut bytes = std::fs::read(&path).unwrap();
+        *bytes.last_mut().unwrap() = 0xff;
+        std::fs::write(&path, bytes).unwrap();
+        assert!(PatternMatrix::load(&path, words()).is_err());
+        let recomputed = PatternMatrix::load_or_compute(&path, words()).unwrap();
+        assert_eq!(recomputed.data(), matrix.data());
+        assert!(PatternMatrix::load(&path, words()).is_ok());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
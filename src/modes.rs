@@ -2,3 +2,20 @@ mod run_all;
 pub use run_all::run_all;
 mod interactive;
 pub use interactive::interactive;
+pub mod protocol;
+mod serve;
+pub use serve::serve;
+mod solve;
+pub use solve::solve;
+mod build_tree;
+pub use build_tree::build_tree;
+mod multi_board;
+pub use multi_board::{multi_interactive, multi_run_all};
+mod absurdle;
+pub use absurdle::{absurdle, absurdle_solver};
+mod compare;
+pub use compare::compare;
+#[cfg(feature = "tui")]
+mod tui;
+#[cfg(feature = "tui")]
+pub use tui::tui;
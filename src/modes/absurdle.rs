@@ -0,0 +1,70 @@
+use crate::{
+    game::{Absurdle, Outcome, Printer},
+    nice_print,
+    words::WordLists,
+    Guesser, Word, Wordle,
+};
+use colored::Colorize;
+use std::{collections::HashSet, io::stdin};
+
+/// Lets somebody play against the adversarial host in the terminal, until the end of the input.
+pub fn absurdle() {
+    let lists = WordLists::global();
+    let dictionary: HashSet<Word> = lists.words().collect();
+    let mut game = Absurdle::new(lists.answers.clone(), &dictionary);
+    println!(
+        "There is no answer yet: every guess gets the pattern that keeps the most words possible."
+    );
+    println!("Type a guess per line. To list the words still possible, type 'REMAINING'.");
+
+    loop {
+        let mut user_input = String::new();
+        if stdin().read_line(&mut user_input).unwrap() == 0 {
+            // end of input
+            return;
+        }
+        let input = user_input.trim();
+        if input == "REMAINING" {
+            for &word in game.candidates() {
+                print!("{} ", nice_print(word));
+            }
+            println!();
+            continue;
+        }
+        let word: Word = match input.to_lowercase().parse() {
+            Ok(word) => word,
+            Err(e) => {
+                println!("Error: {}", e);
+                continue;
+            }
+        };
+        match game.submit(word) {
+            Ok(pattern) if pattern.is_solved() => {
+                println!(
+                    "{} You won in {} guesses.",
+                    pattern,
+                    game.history().len().to_string().blue().bold()
+                );
+                return;
+            }
+            Ok(pattern) => match game.candidates().len() {
+                1 => println!("{} A single word is still possible.", pattern),
+                n => println!("{} {} words are still possible.", pattern, n),
+            },
+            Err(e) => println!("Error: {}", e),
+        }
+    }
+}
+
+/// Plays a single game of the solver against the adversarial host.
+pub fn absurdle_solver<G: Guesser>(mut guesser: G) {
+    let record = Wordle::new().play_absurdle(&mut guesser, &mut Printer);
+    match record.outcome {
+        Outcome::Solved => println!(
+            "The host was left with '{}', took {} tries.",
+            record.answer.to_string().to_uppercase().blue(),
+            record.turns.len().to_string().blue().bold()
+        ),
+        _ => eprintln!("failed to guess"),
+    }
+}
@@ -0,0 +1,28 @@
+use crate::{tree::Tree, words::WordLists, Guesser, Word};
+use std::{io, path::Path};
+
+/// Plays `guesser` on the answers of the word lists (all of them by default),
+/// and saves the tree of its guesses to `path`, in the format given by its extension.
+pub fn build_tree<G: Guesser>(
+    mut guesser: G,
+    num_rounds: Option<usize>,
+    skipped_rounds: Option<usize>,
+    path: &Path,
+) -> io::Result<()> {
+    let answers: Vec<Word> = WordLists::global()
+        .answers
+        .iter()
+        .skip(skipped_rounds.unwrap_or(0))
+        .take(num_rounds.unwrap_or(usize::MAX))
+        .copied()
+        .collect();
+    let tree = Tree::build(&mut guesser, &answers).map_err(io::Error::other)?;
+    tree.save(path)?;
+    println!(
+        "Saved the tree of {} answers ({} distinct histories) to {}",
+        answers.len(),
+        tree.num_nodes(),
+        path.display()
+    );
+    Ok(())
+}
@@ -0,0 +1,186 @@
+use crate::{game::GameRecord, words::WordLists, Guesser, Word, Wordle};
+use colored::Colorize;
+use std::{cmp::Ordering, fmt::Write};
+
+/// Plays the answers of the word lists with both solvers, and shows side by side
+/// the games they don't play the same way, since the overall statistics hide them.
+pub fn compare<A: Guesser, B: Guesser>(
+    mut first: A,
+    mut second: B,
+    num_rounds: Option<usize>,
+    skipped_rounds: Option<usize>,
+) {
+    let answers: Vec<Word> = WordLists::global()
+        .answers
+        .iter()
+        .skip(skipped_rounds.unwrap_or(0))
+        .take(num_rounds.unwrap_or(10))
+        .copied()
+        .collect();
+    // The solvers print as they go: play every game first, to show the differences together.
+    let games = play_both(&mut first, &mut second, &answers);
+
+    let (mut better, mut worse, mut differ) = (0, 0, 0);
+    for (a, b) in &games {
+        if !diverges(a, b) {
+            continue;
+        }
+        differ += 1;
+        match by_score(a, b) {
+            Ordering::Less => better += 1,
+            Ordering::Greater => worse += 1,
+            Ordering::Equal => {}
+        }
+        print!("{}", side_by_side(a, b));
+    }
+
+    let mean = |records: &mut dyn Iterator<Item = &GameRecord>| {
+        let scores: Vec<usize> = records.filter_map(GameRecord::score).collect();
+        scores.iter().sum::<usize>() as f64 / scores.len().max(1) as f64
+    };
+    println!(
+        "{} of {} games are played differently: {} in fewer guesses by the first solver, {} by the second.",
+        differ,
+        games.len(),
+        better,
+        worse
+    );
+    println!(
+        "Mean guesses: {:.4} against {:.4}.",
+        mean(&mut games.iter().map(|(a, _)| a)),
+        mean(&mut games.iter().map(|(_, b)| b))
+    );
+}
+
+/// Every game played by both solvers.
+fn play_both(
+    first: &mut impl Guesser,
+    second: &mut impl Guesser,
+    answers: &[Word],
+) -> Vec<(GameRecord, GameRecord)> {
+    let w = Wordle::new();
+    answers
+        .iter()
+        .map(|answer| (w.play(answer, first), w.play(answer, second)))
+        .collect()
+}
+
+/// Whether the solvers didn't make the same guesses or didn't do as well.
+fn diverges(a: &GameRecord, b: &GameRecord) -> bool {
+    a.outcome != b.outcome
+        || a.turns.len() != b.turns.len()
+        || a.turns
+            .iter()
+            .zip(&b.turns)
+            .any(|(x, y)| x.guess != y.guess)
+}
+
+/// Fewer guesses first, unsolved games last.
+fn by_score(a: &GameRecord, b: &GameRecord) -> Ordering {
+    let key = |r: &GameRecord| r.score().unwrap_or(usize::MAX);
+    key(a).cmp(&key(b))
+}
+
+/// Both games, a turn per line, with a `>` where the guesses differ.
+fn side_by_side(a: &GameRecord, b: &GameRecord) -> String {
+    let score = |r: &GameRecord| r.score().map_or("-".to_string(), |s| s.to_string());
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "{}: {} against {} guesses",
+        a.answer.to_string().to_uppercase().blue(),
+        score(a),
+        score(b)
+    );
+    for i in 0..a.turns.len().max(b.turns.len()) {
+        let cell = |r: &GameRecord| match r.turns.get(i) {
+            Some(turn) => format!("{} {}", turn.guess.to_string().to_uppercase(), turn.pattern),
+            // As wide as a word, a space and five squares of two columns.
+            None => " ".repeat(16),
+        };
+        let same = a.turns.get(i).map(|t| t.guess) == b.turns.get(i).map(|t| t.guess);
+        let _ = writeln!(
+            out,
+            "{} {:>3}  {}   {}",
+            if same { " " } else { ">" },
+            i + 1,
+            cell(a),
+            cell(b)
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diverges, play_both, side_by_side};
+    use crate::algorithms::{HardMode, Naive, Options, Prior};
+    use crate::game::Outcome;
+    use crate::stats::tests::record;
+    use crate::{to_word, Guess, Guesser, Word};
+    use std::sync::Arc;
+
+    #[test]
+    fn divergence() {
+        let a = record("fight", &["tares", "light", "fight"], Outcome::Solved);
+        let b = record("fight", &["tares", "might", "fight"], Outcome::Solved);
+        let c = record("fight", &["tares", "fight"], Outcome::Solved);
+        assert!(!diverges(&a, &a));
+        assert!(diverges(&a, &b));
+        assert!(diverges(&a, &c));
+
+        let shown = side_by_side(&a, &c);
+        let lines: Vec<&str> = shown.lines().collect();
+        assert!(lines[0].ends_with(": 3 against 2 guesses"));
+        assert!(lines[1].starts_with("    1  TARES"));
+        assert!(lines[2].starts_with(">   2  LIGHT"));
+        assert!(lines[3].starts_with(">   3  FIGHT 🟩🟩🟩🟩🟩   "));
+        assert_eq!(lines.len(), 4);
+    }
+
+    /// All words are equally likely, so that the solver often has to break ties.
+    #[derive(Debug)]
+    struct Uniform;
+
+    impl Prior for Uniform {
+        fn weights(&self, dictionary: &[(Word, usize)]) -> Vec<f64> {
+            vec![1.0; dictionary.len()]
+        }
+    }
+
+    /// The naive solver after fixed guesses (the opener would take the pattern matrix),
+    /// only guessing the possible answers: fast enough for a debug build.
+    struct Strict(Naive);
+
+    impl Strict {
+        fn new() -> Self {
+            Self(Naive::with_options(Options {
+                hard_mode: HardMode::Strict,
+                prior: Arc::new(Uniform),
+                ..Options::default()
+            }))
+        }
+    }
+
+    impl Guesser for Strict {
+        fn guess(&mut self, history: &[Guess]) -> Word {
+            match history.len() {
+                0 => {
+                    // A new game.
+                    *self = Self::new();
+                    to_word("tares")
+                }
+                1 => to_word("light"),
+                _ => self.0.guess(history),
+            }
+        }
+    }
+
+    #[test]
+    fn same_solver() {
+        // Both solvers hash the words differently, which must not change their guesses.
+        let answers = ["fight", "might", "night", "wight"].map(to_word);
+        let games = play_both(&mut Strict::new(), &mut Strict::new(), &answers);
+        assert!(games.iter().all(|(a, b)| !diverges(a, b)));
+    }
+}
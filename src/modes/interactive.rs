@@ -1,11 +1,19 @@
-use crate::{nice_print, Correctness, Guess, Guesser, Word};
-use ascii::{AsciiChar, ToAsciiChar};
-use std::io::stdin;
+use crate::algorithms::{Contradiction, HardMode, Interactive, Suggestion};
+use crate::{nice_print, Guess, Guesser, Pattern, Word};
+use std::{
+    fmt, fs,
+    io::{self, stdin},
+    path::{Path, PathBuf},
+};
 
 pub fn error_unrecognized() {
     println!("Error: Command not recognized.");
 }
 
+fn is_tile(ch: char) -> bool {
+    matches!(ch, '-' | '#' | '+' | '⬛' | '⬜' | '🟨' | '🟩')
+}
+
 pub enum CmdToken {
     Unrecognized,
     Remove,
@@ -14,8 +22,16 @@ pub enum CmdToken {
     Consider,
     Remaining,
     Hard,
+    Strict,
+    Undo,
+    History,
+    Edit,
+    Top,
+    Grid,
+    Save,
+    Load,
     Word(Word),
-    Mask([Correctness; 5]),
+    Mask(Pattern),
 }
 
 pub fn parse_cmd(cmd: &str) -> CmdToken {
@@ -31,50 +47,383 @@ pub fn parse_cmd(cmd: &str) -> CmdToken {
         CmdToken::Remaining
     } else if cmd == "HARD" {
         CmdToken::Hard
+    } else if cmd == "STRICT" {
+        CmdToken::Strict
+    } else if cmd == "UNDO" {
+        CmdToken::Undo
+    } else if cmd == "HISTORY" {
+        CmdToken::History
+    } else if cmd == "EDIT" {
+        CmdToken::Edit
+    } else if cmd == "TOP" {
+        CmdToken::Top
+    } else if cmd == "GRID" {
+        CmdToken::Grid
+    } else if cmd == "SAVE" {
+        CmdToken::Save
+    } else if cmd == "LOAD" {
+        CmdToken::Load
+    } else if cmd.starts_with(is_tile) {
+        // cmd is a mask pattern, in the `-#+` notation or as copied from a share grid,
+        // where the squares may be followed by an invisible variation selector.
+        match cmd.replace('\u{fe0f}', "").parse() {
+            Ok(mask) => CmdToken::Mask(mask),
+            Err(_) => CmdToken::Unrecognized,
+        }
     } else if cmd.len() == 5 {
-        let identifier = cmd.chars().next().unwrap();
-        if identifier == '-' || identifier == '#' || identifier == '+' {
-            // cmd is a mask pattern.
-            let mut mask = Vec::<Correctness>::with_capacity(5);
-            for char in cmd.chars() {
-                match char {
-                    '-' => {
-                        mask.push(Correctness::Wrong);
-                    }
-                    '#' => {
-                        mask.push(Correctness::Correct);
-                    }
-                    '+' => {
-                        mask.push(Correctness::Misplaced);
-                    }
-                    _ => {
-                        return CmdToken::Unrecognized;
-                    }
-                };
+        // cmd is a word.
+        match cmd.parse() {
+            Ok(word) => CmdToken::Word(word),
+            Err(_) => CmdToken::Unrecognized,
+        }
+    } else {
+        CmdToken::Unrecognized
+    }
+}
+
+/// What the user told the guesser, in the order they did: the guesser can be
+/// rewound to any point by replaying the entries before it on a fresh one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Entry {
+    Guess(Guess),
+    Remove(Word),
+    Eliminate(Word),
+    Allow(Word),
+    Consider(Word),
+    Hard(HardMode),
+}
+
+impl Entry {
+    /// Tells the guesser, which must have observed every guess of `history` already,
+    /// and has it catch up as asking for a guess would.
+    pub(super) fn apply(&self, guesser: &mut Interactive, history: &mut Vec<Guess>) {
+        match *self {
+            Entry::Guess(guess) => history.push(guess),
+            Entry::Remove(word) => guesser.remove(&word),
+            Entry::Eliminate(word) => guesser.eliminate(&word),
+            Entry::Allow(word) => guesser.add(&word),
+            Entry::Consider(word) => guesser.consider(&word),
+            Entry::Hard(mode) => guesser.hard(mode),
+        }
+        guesser.observe(history);
+    }
+
+    /// What was understood, when the entry is typed.
+    fn acknowledge(&self) {
+        match *self {
+            Entry::Guess(_) => {}
+            Entry::Remove(word) => println!(
+                "Adjusted to the fact that {} is not allowed.",
+                nice_print(word)
+            ),
+            Entry::Eliminate(word) => println!(
+                "Adjusted to the assumption that {} is not the answer.",
+                nice_print(word)
+            ),
+            Entry::Allow(word) => {
+                println!("Adjusted to the fact that {} is allowed.", nice_print(word))
             }
-            let mask = mask.try_into().unwrap();
-            CmdToken::Mask(mask)
-        } else {
-            // cmd is a word.
-            let mut word = Vec::<AsciiChar>::with_capacity(5);
-            for char in cmd.chars() {
-                if let Ok(char) = char.to_ascii_char() {
-                    word.push(char);
-                } else {
-                    return CmdToken::Unrecognized;
+            Entry::Consider(word) => println!(
+                "Adjusted to the assumption that {} might be the answer.",
+                nice_print(word)
+            ),
+            Entry::Hard(HardMode::Strict) => println!("Strict hard mode activated."),
+            Entry::Hard(_) => println!("Hard mode activated."),
+        }
+    }
+}
+
+/// As the entry would be typed.
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Entry::Guess(guess) => write!(f, "{} {}", guess.word, guess.mask.to_text()),
+            Entry::Remove(word) => write!(f, "REMOVE {}", word),
+            Entry::Eliminate(word) => write!(f, "ELIMINATE {}", word),
+            Entry::Allow(word) => write!(f, "ALLOW {}", word),
+            Entry::Consider(word) => write!(f, "CONSIDER {}", word),
+            Entry::Hard(HardMode::Strict) => write!(f, "HARD STRICT"),
+            Entry::Hard(_) => write!(f, "HARD"),
+        }
+    }
+}
+
+/// A line of input.
+#[derive(Debug, PartialEq, Eq)]
+enum Command {
+    Entry(Entry),
+    Remaining,
+    Undo,
+    History,
+    /// Replaces the entry of the given number, or deletes it.
+    Edit(usize, Option<Entry>),
+    /// Number of guesses to rank along with every suggestion.
+    Top(usize),
+    /// The words of a share grid, and the rows of the grid given so far.
+    Grid(Vec<Word>, Vec<Pattern>),
+    Save(PathBuf),
+    /// Replaces the session with the one of the file.
+    Load(PathBuf),
+}
+
+/// `suggestion` is the word the commands omitting it are about.
+fn parse_entry(args: &[&str], suggestion: Option<Word>) -> Option<Entry> {
+    let word = |arg: &str| match parse_cmd(arg) {
+        CmdToken::Word(word) => Some(word),
+        _ => None,
+    };
+    let word_or_suggestion = |rest: &[&str]| match rest {
+        [] => suggestion,
+        [arg] => word(arg),
+        _ => None,
+    };
+    let (first, rest) = args.split_first()?;
+    match parse_cmd(first) {
+        CmdToken::Remove => word_or_suggestion(rest).map(Entry::Remove),
+        CmdToken::Eliminate => word_or_suggestion(rest).map(Entry::Eliminate),
+        CmdToken::Allow => match rest {
+            [arg] => word(arg).map(Entry::Allow),
+            _ => None,
+        },
+        CmdToken::Consider => match rest {
+            [arg] => word(arg).map(Entry::Consider),
+            _ => None,
+        },
+        CmdToken::Hard => match rest {
+            [] => Some(Entry::Hard(HardMode::Nyt)),
+            [arg] => match parse_cmd(arg) {
+                CmdToken::Strict => Some(Entry::Hard(HardMode::Strict)),
+                _ => None,
+            },
+            _ => None,
+        },
+        CmdToken::Mask(mask) => match rest {
+            [] => suggestion.map(|word| Entry::Guess(Guess { word, mask })),
+            _ => None,
+        },
+        CmdToken::Word(word) => match rest {
+            [arg] => match parse_cmd(arg) {
+                CmdToken::Mask(mask) => Some(Entry::Guess(Guess { word, mask })),
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn parse_line(line: &str, suggestion: Word, entries: &[Entry]) -> Option<Command> {
+    let args: Vec<&str> = line.split_whitespace().collect();
+    match parse_cmd(args.first()?) {
+        CmdToken::Remaining if args.len() == 1 => Some(Command::Remaining),
+        CmdToken::Undo if args.len() == 1 => Some(Command::Undo),
+        CmdToken::History if args.len() == 1 => Some(Command::History),
+        CmdToken::Top if args.len() == 2 => args[1].parse().ok().map(Command::Top),
+        CmdToken::Save if args.len() > 1 => Some(Command::Save(args[1..].join(" ").into())),
+        CmdToken::Load if args.len() > 1 => Some(Command::Load(args[1..].join(" ").into())),
+        CmdToken::Grid => {
+            let mut words = Vec::new();
+            let mut rows = Vec::new();
+            for arg in &args[1..] {
+                match parse_cmd(arg) {
+                    CmdToken::Word(word) if rows.is_empty() => words.push(word),
+                    CmdToken::Mask(mask) => rows.push(mask),
+                    _ => return None,
                 }
             }
-            let word = word.try_into().unwrap();
-            CmdToken::Word(word)
+            (!words.is_empty() && rows.len() <= words.len()).then_some(Command::Grid(words, rows))
+        }
+        CmdToken::Edit => {
+            let n: usize = args.get(1)?.parse().ok()?;
+            let edited = entries.get(n.checked_sub(1)?)?;
+            if args.len() == 2 {
+                return Some(Command::Edit(n, None));
+            }
+            // The word of the edited entry can be omitted, to only fix its pattern.
+            let word = match *edited {
+                Entry::Guess(guess) => Some(guess.word),
+                Entry::Remove(word)
+                | Entry::Eliminate(word)
+                | Entry::Allow(word)
+                | Entry::Consider(word) => Some(word),
+                Entry::Hard(_) => None,
+            };
+            let entry = parse_entry(&args[2..], word)?;
+            Some(Command::Edit(n, Some(entry)))
+        }
+        _ => parse_entry(&args, Some(suggestion)).map(Command::Entry),
+    }
+}
+
+const SESSION_HEADING: &str = "# rogerthat interactive session: a line per entry, as typed.";
+
+/// The entries of a session file: one per line, as typed, the word always given.
+/// Blank lines and lines starting with "# " are ignored.
+fn parse_session(text: &str) -> Result<Vec<Entry>, String> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with("# "))
+        .map(|(i, line)| {
+            let args: Vec<&str> = line.split_whitespace().collect();
+            parse_entry(&args, None)
+                .ok_or_else(|| format!("line {} is not an entry: {}", i + 1, line))
+        })
+        .collect()
+}
+
+fn format_session(entries: &[Entry]) -> String {
+    let mut text = format!("{}\n", SESSION_HEADING);
+    for entry in entries {
+        text += &format!("{}\n", entry);
+    }
+    text
+}
+
+pub(super) fn save(path: &Path, entries: &[Entry]) -> io::Result<()> {
+    fs::write(path, format_session(entries))
+}
+
+pub(super) fn load(path: &Path) -> io::Result<Vec<Entry>> {
+    parse_session(&fs::read_to_string(path)?)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Keeps the session file up to date, if there is one.
+fn autosave(session: Option<&Path>, entries: &[Entry]) {
+    if let Some(path) = session {
+        if let Err(e) = save(path, entries) {
+            println!(
+                "Error: Could not save the session to {}: {}",
+                path.display(),
+                e
+            );
         }
+    }
+}
+
+/// Number of corrections listed for a contradiction.
+const CORRECTIONS: usize = 8;
+
+/// What is wrong with a guess contradicting the history: `label` names the guesses
+/// of the history by index, the new one being at the index after the last.
+pub(super) fn explain(
+    contradiction: &Contradiction,
+    history: &[Guess],
+    label: impl Fn(usize) -> String,
+) -> Vec<String> {
+    if contradiction.eliminated {
+        return vec![
+            "Only words assumed not to be the answer fit: use 'CONSIDER' to bring them back."
+                .to_string(),
+        ];
+    }
+    let mut lines = Vec::new();
+    if contradiction.conflicts.is_empty() {
+        lines.push("No word fits this pattern.".to_string());
     } else {
-        CmdToken::Unrecognized
+        lines.push(
+            "No word fits this pattern along with the earlier ones. It contradicts:".to_string(),
+        );
+        for &i in &contradiction.conflicts {
+            lines.push(format!("  {}: {}", label(i), Entry::Guess(history[i])));
+        }
+    }
+    if !contradiction.corrections.is_empty() {
+        lines.push("Changing a single tile would fit:".to_string());
+        for &(i, guess) in contradiction.corrections.iter().take(CORRECTIONS) {
+            lines.push(format!("  {}: {}", label(i), Entry::Guess(guess)));
+        }
     }
+    lines
 }
 
-pub fn interactive() {
+/// Whether `guess` can come after `history`, explaining why not.
+fn check(guesser: &Interactive, entries: &[Entry], history: &[Guess], guess: &Guess) -> bool {
+    match guesser.validate(history, guess) {
+        Ok(()) => true,
+        Err(contradiction) => {
+            // The line numbers of the guesses, as listed by 'HISTORY'.
+            let lines: Vec<usize> = entries
+                .iter()
+                .enumerate()
+                .filter(|(_, entry)| matches!(entry, Entry::Guess(_)))
+                .map(|(n, _)| n + 1)
+                .collect();
+            let label = |i: usize| match lines.get(i) {
+                Some(n) => format!("line {}", n),
+                None => "this line".to_string(),
+            };
+            println!("Error: The pattern was not taken into account.");
+            for line in explain(&contradiction, history, label) {
+                println!("{}", line);
+            }
+            false
+        }
+    }
+}
+
+/// A fresh guesser told all the entries, and the history of their guesses.
+pub(super) fn replay(
+    new_guesser: &impl Fn() -> Interactive,
+    list_size: usize,
+    entries: &[Entry],
+) -> (Interactive, Vec<Guess>) {
+    let mut guesser = new_guesser();
+    guesser.set_list_size(list_size);
+    let mut history = Vec::new();
+    for entry in entries {
+        entry.apply(&mut guesser, &mut history);
+    }
+    (guesser, history)
+}
+
+/// The rows of a share grid on a line, ignoring the lines that have none,
+/// like the "Wordle 1,234 4/6" heading.
+fn grid_rows(line: &str) -> Option<Vec<Pattern>> {
+    line.split_whitespace()
+        .filter(|arg| arg.starts_with(is_tile))
+        .map(|arg| match parse_cmd(arg) {
+            CmdToken::Mask(mask) => Some(mask),
+            _ => None,
+        })
+        .collect()
+}
+
+fn read_line() -> Option<String> {
+    let mut user_input = String::new();
+    match stdin().read_line(&mut user_input).unwrap() {
+        // end of input
+        0 => None,
+        _ => Some(user_input),
+    }
+}
+
+fn print_ranking(ranking: &[Suggestion]) {
+    println!("  #  Guess  Information  Expected remaining  Answer probability  Possible");
+    for (i, s) in ranking.iter().enumerate() {
+        println!(
+            "{:>3}  {}  {:>6.3} bits  {:>18.2}  {:>17.2}%  {}",
+            i + 1,
+            nice_print(s.word),
+            s.information,
+            s.expected_remaining,
+            s.probability * 100.0,
+            if s.possible { "yes" } else { "no" }
+        );
+    }
+}
+
+/// Helps with a game played elsewhere, until the end of the input.
+/// `new_guesser` gives the guesser to start from, and to replay the entries on when rewinding.
+/// The `list_size` best guesses are listed with every suggestion, until changed with `TOP`.
+/// The session is resumed from the `session` file if it exists, and kept in it as it goes.
+pub fn interactive(
+    new_guesser: impl Fn() -> Interactive,
+    mut list_size: usize,
+    session: Option<&Path>,
+) {
     println!("Type history. Each line is: word + space + pattern.");
-    println!("'-' for Wrong/Gray, '#' for Correct/Green, '+' for Misplaced/Yellow.");
+    println!("'-' for Wrong/Gray, '#' for Correct/Green, '+' for Misplaced/Yellow, or the squares of a share grid.");
     println!("If the suggestion is not allowed, type 'REMOVE'.");
     println!("You can also manually remove any word by typing 'REMOVE' + space + word.");
     println!("If a word is allowed but you don't think it's the answer, use 'ELIMINATE' instead of 'REMOVE'.");
@@ -82,88 +431,274 @@ pub fn interactive() {
     println!("To allow a word and consider it as possibly the answer (the opposite of and undoes 'ELIMINATE'), use 'CONSIDER'.");
     println!("If you follow the suggestion, you can just type the pattern, omitting the word (and the space).");
     println!("To list all remaining possible words, type 'REMAINING'.");
-    println!("To enter hard mode, type 'HARD'.");
+    println!("To enter hard mode, type 'HARD'. Suggestions will then use all revealed hints.");
+    println!("For suggestions that could all be the answer, type 'HARD STRICT' instead.");
+    println!("To take back the last line, type 'UNDO'. To list every line so far, type 'HISTORY'.");
+    println!("To fix line n of the history, type 'EDIT' + space + n + space + the line as it should have been");
+    println!("(the word can be omitted to only change the pattern), or just 'EDIT' + space + n to delete it.");
+    println!("To paste a share grid, type 'GRID' + space + the words played, separated by spaces, then paste the grid.");
+    println!("To keep the session in a file, type 'SAVE' + space + file. To go on with a saved one, 'LOAD' + space + file.");
+    println!("To list the n best guesses with every suggestion, type 'TOP' + space + n ('TOP 0' to stop).");
 
-    let mut guesser = crate::algorithms::Interactive::new();
-    let mut history = Vec::<Guess>::new();
+    let mut entries = Vec::<Entry>::new();
+    if let Some(path) = session.filter(|path| path.exists()) {
+        match load(path) {
+            Ok(loaded) => {
+                println!("Resumed the session of {}:", path.display());
+                for (i, entry) in loaded.iter().enumerate() {
+                    println!("{:>3}. {}", i + 1, entry);
+                }
+                entries = loaded;
+            }
+            Err(e) => {
+                eprintln!("failed to resume the session of {}: {}", path.display(), e);
+                return;
+            }
+        }
+    }
+    let (mut guesser, mut history) = replay(&new_guesser, list_size, &entries);
 
     loop {
         let guess = guesser.guess(&history);
         println!("Suggested guess is: {}", nice_print(guess));
+        if !guesser.ranking().is_empty() {
+            print_ranking(guesser.ranking());
+        }
 
-        let mut user_input = String::new();
-        let buzz = stdin();
-        buzz.read_line(&mut user_input).unwrap();
-        let mut user_slice_iter = user_input.trim().split_whitespace();
-
-        let arg1 = user_slice_iter.next();
-        let arg2 = user_slice_iter.next();
-
-        if let Some(arg1) = arg1 {
-            let arg1 = parse_cmd(arg1);
-            match arg1 {
-                CmdToken::Remove => {
-                    if let Some(arg2) = arg2 {
-                        if let CmdToken::Word(word) = parse_cmd(arg2) {
-                            guesser.remove(&word);
-                            continue;
-                        }
-                    } else {
-                        guesser.remove(&guess);
+        let Some(user_input) = read_line() else {
+            return;
+        };
+
+        let command = match parse_line(&user_input, guess, &entries) {
+            Some(command) => command,
+            None => {
+                error_unrecognized();
+                continue;
+            }
+        };
+        match command {
+            Command::Entry(entry) => {
+                if let Entry::Guess(guess) = entry {
+                    if !check(&guesser, &entries, &history, &guess) {
                         continue;
                     }
                 }
-                CmdToken::Eliminate => {
-                    if let Some(arg2) = arg2 {
-                        if let CmdToken::Word(word) = parse_cmd(arg2) {
-                            guesser.eliminate(&word);
-                            continue;
-                        }
-                    } else {
-                        guesser.eliminate(&guess);
-                        continue;
-                    }
+                entry.apply(&mut guesser, &mut history);
+                entry.acknowledge();
+                entries.push(entry);
+                autosave(session, &entries);
+                continue;
+            }
+            Command::Remaining => {
+                guesser.remaining();
+                continue;
+            }
+            Command::Grid(words, mut rows) => {
+                if rows.len() < words.len() {
+                    println!("Paste the grid: {} more rows.", words.len() - rows.len());
                 }
-                CmdToken::Allow => {
-                    if let Some(arg2) = arg2 {
-                        if let CmdToken::Word(word) = parse_cmd(arg2) {
-                            guesser.add(&word);
-                            continue;
-                        }
+                while rows.len() < words.len() {
+                    let Some(line) = read_line() else {
+                        return;
+                    };
+                    match grid_rows(&line) {
+                        Some(more) => rows.extend(more),
+                        None => break,
                     }
                 }
-                CmdToken::Consider => {
-                    if let Some(arg2) = arg2 {
-                        if let CmdToken::Word(word) = parse_cmd(arg2) {
-                            guesser.consider(&word);
-                            continue;
-                        }
+                if rows.len() != words.len() {
+                    println!(
+                        "Error: The grid should have a row per word, {} rows.",
+                        words.len()
+                    );
+                    continue;
+                }
+                for (word, mask) in words.into_iter().zip(rows) {
+                    let guess = Guess { word, mask };
+                    if !check(&guesser, &entries, &history, &guess) {
+                        // The rows before are kept.
+                        break;
                     }
+                    let entry = Entry::Guess(guess);
+                    entry.apply(&mut guesser, &mut history);
+                    entries.push(entry);
                 }
-                CmdToken::Remaining => {
-                    guesser.remaining();
-                    continue;
+                autosave(session, &entries);
+                continue;
+            }
+            Command::Save(path) => {
+                match save(&path, &entries) {
+                    Ok(()) => println!("Saved the session to {}.", path.display()),
+                    Err(e) => println!("Error: Could not save the session: {}", e),
                 }
-                CmdToken::Hard => {
-                    guesser.hard();
-                    println!("Hard mode activated.");
-                    continue;
+                continue;
+            }
+            Command::Load(path) => match load(&path) {
+                Ok(loaded) => {
+                    println!("Loaded {} lines from {}.", loaded.len(), path.display());
+                    entries = loaded;
                 }
-                CmdToken::Mask(mask) => {
-                    history.push(Guess { word: guess, mask });
+                Err(e) => {
+                    println!("Error: Could not load the session: {}", e);
                     continue;
                 }
-                CmdToken::Word(word) => {
-                    if let Some(arg2) = arg2 {
-                        if let CmdToken::Mask(mask) = parse_cmd(arg2) {
-                            history.push(Guess { word, mask });
-                            continue;
-                        }
-                    }
+            },
+            Command::Top(n) => {
+                list_size = n;
+                guesser.set_list_size(list_size);
+                continue;
+            }
+            Command::History => {
+                if entries.is_empty() {
+                    println!("Nothing was typed yet.");
                 }
-                CmdToken::Unrecognized => {}
+                for (i, entry) in entries.iter().enumerate() {
+                    println!("{:>3}. {}", i + 1, entry);
+                }
+                continue;
+            }
+            Command::Undo => match entries.pop() {
+                Some(entry) => println!("Took back: {}", entry),
+                None => {
+                    println!("Nothing to take back.");
+                    continue;
+                }
+            },
+            Command::Edit(n, Some(entry)) => {
+                println!("Line {} is now: {}", n, entry);
+                entries[n - 1] = entry;
             }
+            Command::Edit(n, None) => {
+                println!("Deleted line {}: {}", n, entries.remove(n - 1));
+            }
+        }
+
+        // Rewind: the guesser only ever narrows down, so start over.
+        (guesser, history) = replay(&new_guesser, list_size, &entries);
+        autosave(session, &entries);
+        if !history.is_empty() && guesser.remaining_count() == 0 {
+            println!(
+                "Warning: No word fits the history any more. See 'HISTORY', then 'UNDO' or 'EDIT'."
+            );
         }
-        error_unrecognized();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_session, grid_rows, parse_line, parse_session, replay, Command, Entry};
+    use crate::algorithms::{HardMode, Interactive};
+    use crate::{to_word, Guess, Pattern};
+
+    #[test]
+    fn commands() {
+        let tares = to_word("tares");
+        let guess = Guess {
+            word: tares,
+            mask: "-+#--".parse().unwrap(),
+        };
+        let entries = [Entry::Guess(guess), Entry::Remove(to_word("clout"))];
+
+        assert_eq!(
+            parse_line("-+#--\n", tares, &[]),
+            Some(Command::Entry(Entry::Guess(guess)))
+        );
+        assert_eq!(
+            parse_line("REMOVE", tares, &[]),
+            Some(Command::Entry(Entry::Remove(tares)))
+        );
+        assert_eq!(
+            parse_line("HARD STRICT", tares, &[]),
+            Some(Command::Entry(Entry::Hard(HardMode::Strict)))
+        );
+        assert_eq!(parse_line("UNDO", tares, &entries), Some(Command::Undo));
+        assert_eq!(parse_line("TOP 5", tares, &entries), Some(Command::Top(5)));
+        assert_eq!(parse_line("TOP", tares, &entries), None);
+
+        // Share grids use emoji squares.
+        assert_eq!(
+            parse_line("tares ⬛🟨🟩⬜⬛", to_word("clout"), &[]),
+            Some(Command::Entry(Entry::Guess(guess)))
+        );
+        assert_eq!(
+            parse_line("GRID tares clout 🟩\u{fe0f}🟩🟩🟩🟩", tares, &[]),
+            Some(Command::Grid(
+                vec![tares, to_word("clout")],
+                vec![Pattern::SOLVED]
+            ))
+        );
+        assert_eq!(parse_line("GRID ⬛🟨🟩⬜⬛ tares", tares, &[]), None);
+        assert_eq!(grid_rows("Wordle 1,234 4/6*\n"), Some(vec![]));
+        assert_eq!(
+            grid_rows("⬛🟨🟩⬛⬛ 🟩🟩🟩🟩🟩"),
+            Some(vec![guess.mask, Pattern::SOLVED])
+        );
+        assert_eq!(grid_rows("⬛🟨🟩⬛"), None);
+        assert_eq!(parse_line("ALLOW", tares, &entries), None);
+
+        // The pattern of a guess can be fixed alone.
+        let fixed = Guess {
+            word: tares,
+            mask: "-+---".parse().unwrap(),
+        };
+        assert_eq!(
+            parse_line("EDIT 1 -+---", to_word("clout"), &entries),
+            Some(Command::Edit(1, Some(Entry::Guess(fixed))))
+        );
+        assert_eq!(
+            parse_line("EDIT 2 ELIMINATE", tares, &entries),
+            Some(Command::Edit(2, Some(Entry::Eliminate(to_word("clout")))))
+        );
+        assert_eq!(
+            parse_line("EDIT 2", tares, &entries),
+            Some(Command::Edit(2, None))
+        );
+        assert_eq!(parse_line("EDIT 3", tares, &entries), None);
+        assert_eq!(parse_line("EDIT 0", tares, &entries), None);
+
+        // Entries are shown as they would be typed.
+        for entry in entries {
+            assert_eq!(
+                parse_line(&entry.to_string(), tares, &[]),
+                Some(Command::Entry(entry))
+            );
+        }
+    }
+
+    #[test]
+    fn session() {
+        let entries = [
+            Entry::Hard(HardMode::Nyt),
+            Entry::Guess(Guess {
+                word: to_word("tares"),
+                mask: "-+#--".parse().unwrap(),
+            }),
+            Entry::Remove(to_word("clout")),
+            Entry::Eliminate(to_word("aloud")),
+            Entry::Allow(to_word("zzzzz")),
+            Entry::Consider(to_word("biffy")),
+            Entry::Hard(HardMode::Strict),
+        ];
+        let text = format_session(&entries);
+        assert!(text.contains("\ntares -+#--\nREMOVE clout\n"));
+        assert_eq!(parse_session(&text), Ok(entries.to_vec()));
+
+        // The word can't be left out in a file.
+        assert_eq!(
+            parse_session("tares -+#--\n\n-+#--\n"),
+            Err("line 3 is not an entry: -+#--".to_string())
+        );
+    }
+
+    #[test]
+    fn before_the_first_guess() {
+        let all = Interactive::new().remaining_count();
+        // As after UNDO, EDIT or LOAD.
+        let (mut guesser, history) =
+            replay(&Interactive::new, 0, &[Entry::Eliminate(to_word("tares"))]);
+        assert_eq!(guesser.remaining_count(), all - 1);
+        // Asking for the first guess doesn't bring it back either.
+        guesser.observe(&history);
+        assert_eq!(guesser.remaining_count(), all - 1);
     }
 }
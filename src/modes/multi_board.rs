@@ -0,0 +1,155 @@
+use super::interactive::error_unrecognized;
+use crate::algorithms::MultiBoard;
+use crate::{nice_print, words::WordLists, Guess, MultiGuesser, Pattern, Word, Wordle};
+use colored::Colorize;
+use std::io::stdin;
+
+/// Plays the answers of the word lists, `boards` consecutive answers per game.
+pub fn multi_run_all<G: MultiGuesser>(
+    mut guesser: G,
+    boards: usize,
+    num_rounds: Option<usize>,
+    skipped_rounds: Option<usize>,
+) {
+    let w = Wordle::new();
+    let games = WordLists::global()
+        .answers
+        .chunks_exact(boards)
+        .skip(skipped_rounds.unwrap_or(0))
+        .take(num_rounds.unwrap_or(10));
+    for answers in games {
+        println!("{}", "New game".blue());
+        let record = w.play_multi(answers, &mut guesser);
+        for (i, guess) in record.guesses.iter().enumerate() {
+            let patterns: Vec<String> = answers
+                .iter()
+                .zip(&record.solved)
+                .map(|(answer, solved)| match solved {
+                    Some(n) if *n <= i => ".".to_string(),
+                    _ => Pattern::compute(answer, guess).to_string(),
+                })
+                .collect();
+            println!(
+                "Guessed '{}', received patterns: {}",
+                nice_print(*guess),
+                patterns.join(" ")
+            );
+        }
+        let answers: Vec<String> = answers
+            .iter()
+            .map(|a| a.to_string().to_uppercase())
+            .collect();
+        match record.score() {
+            Some(score) => println!(
+                "The answers are '{}', took {} tries.",
+                answers.join(" ").blue(),
+                score.to_string().blue().bold()
+            ),
+            None => eprintln!("failed to guess"),
+        }
+    }
+}
+
+/// Helps with a multi-board game played elsewhere, until the end of the input.
+pub fn multi_interactive(mut guesser: MultiBoard) {
+    let n = guesser.num_boards();
+    println!("Type history. Each line is: word + space + {n} patterns, one per board.");
+    println!("'-' for Wrong/Gray, '#' for Correct/Green, '+' for Misplaced/Yellow.");
+    println!("For the boards already solved, type '.' or leave them out altogether.");
+    println!("If you follow the suggestion, you can just type the patterns, omitting the word.");
+    println!("To list all remaining possible words of every board, type 'REMAINING'.");
+
+    let mut histories: Vec<Vec<Guess>> = vec![Vec::new(); n];
+
+    loop {
+        let solved: Vec<bool> = histories
+            .iter()
+            .map(|h| h.last().is_some_and(|g| g.mask.is_solved()))
+            .collect();
+        if !solved.contains(&false) {
+            println!("Every board is solved!");
+            return;
+        }
+
+        let guess = guesser.guess(&histories);
+        println!("Suggested guess is: {}", nice_print(guess));
+
+        let mut user_input = String::new();
+        if stdin().read_line(&mut user_input).unwrap() == 0 {
+            // end of input
+            return;
+        }
+        let mut tokens: Vec<&str> = user_input.split_whitespace().collect();
+        if tokens == ["REMAINING"] {
+            for (i, remaining) in guesser.remaining().enumerate() {
+                print!("Board {}:", i + 1);
+                match remaining {
+                    Some(remaining) => remaining
+                        .keys()
+                        .for_each(|&word| print!(" {}", nice_print(word))),
+                    None => print!(" solved"),
+                }
+                println!();
+            }
+            continue;
+        }
+
+        let word = match tokens.first().and_then(|t| t.parse::<Word>().ok()) {
+            Some(word) => {
+                tokens.remove(0);
+                word
+            }
+            None => guess,
+        };
+        match parse_patterns(&tokens, &solved) {
+            Some(patterns) => {
+                for (history, mask) in histories.iter_mut().zip(patterns) {
+                    if let Some(mask) = mask {
+                        history.push(Guess { word, mask });
+                    }
+                }
+            }
+            None => error_unrecognized(),
+        }
+    }
+}
+
+/// The pattern of every board that isn't `solved` yet, given either for every board
+/// (with `.` for the solved ones) or only for the boards that aren't solved.
+fn parse_patterns(tokens: &[&str], solved: &[bool]) -> Option<Vec<Option<Pattern>>> {
+    let unsolved = solved.iter().filter(|&&s| !s).count();
+    let mut tokens = tokens.iter();
+    let all = match tokens.len() {
+        n if n == solved.len() => true,
+        n if n == unsolved => false,
+        _ => return None,
+    };
+    solved
+        .iter()
+        .map(|&solved| match (solved, all) {
+            (true, false) => Some(None),
+            (true, true) => (*tokens.next()? == ".").then_some(None),
+            (false, _) => tokens.next()?.parse().ok().map(Some),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_patterns;
+
+    #[test]
+    fn patterns() {
+        let solved = [false, true, false];
+        let expected = Some(vec![
+            Some("-+-#-".parse().unwrap()),
+            None,
+            Some("#####".parse().unwrap()),
+        ]);
+        assert_eq!(parse_patterns(&["-+-#-", ".", "#####"], &solved), expected);
+        assert_eq!(parse_patterns(&["-+-#-", "#####"], &solved), expected);
+        assert_eq!(parse_patterns(&["-+-#-", "-----", "#####"], &solved), None);
+        assert_eq!(parse_patterns(&["-+-#-"], &solved), None);
+        assert_eq!(parse_patterns(&["-+-#-", "right"], &solved), None);
+    }
+}
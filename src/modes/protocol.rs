@@ -0,0 +1,309 @@
+//! The line-based protocol between `serve` and `solve`.
+//!
+//! Every message is one line of text. Right after connecting, the server sends
+//! `ROGERTHAT 1` (the protocol version), then plays games with the client one after another:
+//!
+//! ```text
+//! server: GAME <guesses allowed>
+//! client: GUESS <word>
+//! server: PATTERN <pattern>       the guess was not the answer, guess again
+//! server: REJECTED <reason>       the guess is not allowed, it doesn't use up a guess
+//! server: SOLVED <guesses taken>  the game is over
+//! server: FAILED <answer>         out of guesses, the game is over
+//! ```
+//!
+//! Instead of guessing, the client can send `RESIGN` to give up the current game.
+//! Patterns are in the `-#+` notation. Once there are no more games, the server sends `BYE`.
+
+use crate::{Pattern, Word};
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::{
+    fmt,
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    path::PathBuf,
+    str::FromStr,
+};
+
+pub const VERSION: u32 = 1;
+
+/// Where the server listens: a localhost TCP address like `127.0.0.1:7878`,
+/// or the path of a Unix socket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Address {
+    Tcp(SocketAddr),
+    Unix(PathBuf),
+}
+
+impl FromStr for Address {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse::<SocketAddr>() {
+            // There is no authentication whatsoever, so don't let anyone else in.
+            Ok(addr) if !addr.ip().is_loopback() => {
+                Err(format!("{} is not a localhost address", addr))
+            }
+            Ok(addr) => Ok(Self::Tcp(addr)),
+            Err(_) if cfg!(unix) => Ok(Self::Unix(s.into())),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tcp(addr) => write!(f, "{}", addr),
+            Self::Unix(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServerMessage {
+    Hello { version: u32 },
+    Game { max_guesses: usize },
+    Pattern(Pattern),
+    Rejected(String),
+    Solved(usize),
+    Failed(Word),
+    Bye,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClientMessage {
+    Guess(Word),
+    Resign,
+}
+
+impl fmt::Display for ServerMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Hello { version } => write!(f, "ROGERTHAT {}", version),
+            Self::Game { max_guesses } => write!(f, "GAME {}", max_guesses),
+            Self::Pattern(pattern) => write!(f, "PATTERN {}", pattern.to_text()),
+            // The reason must stay on one line.
+            Self::Rejected(reason) => write!(f, "REJECTED {}", reason.replace('\n', " ")),
+            Self::Solved(guesses) => write!(f, "SOLVED {}", guesses),
+            Self::Failed(answer) => write!(f, "FAILED {}", answer),
+            Self::Bye => write!(f, "BYE"),
+        }
+    }
+}
+
+impl fmt::Display for ClientMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Guess(word) => write!(f, "GUESS {}", word),
+            Self::Resign => write!(f, "RESIGN"),
+        }
+    }
+}
+
+impl FromStr for ServerMessage {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (command, arg) = split(s);
+        match command {
+            "ROGERTHAT" => Ok(Self::Hello {
+                version: number(arg)?,
+            }),
+            "GAME" => Ok(Self::Game {
+                max_guesses: number(arg)?,
+            }),
+            "PATTERN" => arg
+                .parse()
+                .map(Self::Pattern)
+                .map_err(|e| format!("invalid pattern {:?}: {}", arg, e)),
+            "REJECTED" => Ok(Self::Rejected(arg.to_string())),
+            "SOLVED" => Ok(Self::Solved(number(arg)?)),
+            "FAILED" => arg
+                .parse()
+                .map(Self::Failed)
+                .map_err(|e| format!("invalid word {:?}: {}", arg, e)),
+            "BYE" => Ok(Self::Bye),
+            _ => Err(format!("unknown message {:?}", s)),
+        }
+    }
+}
+
+impl FromStr for ClientMessage {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match split(s) {
+            ("GUESS", word) => word
+                .parse()
+                .map(Self::Guess)
+                .map_err(|e| format!("invalid word {:?}: {}", word, e)),
+            ("RESIGN", "") => Ok(Self::Resign),
+            _ => Err(format!("unknown message {:?}", s)),
+        }
+    }
+}
+
+fn number<T: FromStr>(arg: &str) -> Result<T, String> {
+    arg.parse().map_err(|_| format!("invalid number {:?}", arg))
+}
+
+/// The command and the rest of the line.
+fn split(s: &str) -> (&str, &str) {
+    let s = s.trim();
+    match s.split_once(' ') {
+        Some((command, arg)) => (command, arg.trim()),
+        None => (s, ""),
+    }
+}
+
+/// One end of a connection, over either kind of socket.
+pub struct Connection {
+    reader: BufReader<Box<dyn Read + Send>>,
+    writer: Box<dyn Write + Send>,
+}
+
+impl Connection {
+    pub fn connect(address: &Address) -> io::Result<Self> {
+        match address {
+            Address::Tcp(addr) => Self::tcp(TcpStream::connect(addr)?),
+            #[cfg(unix)]
+            Address::Unix(path) => Self::unix(UnixStream::connect(path)?),
+            #[cfg(not(unix))]
+            Address::Unix(_) => Err(io::ErrorKind::Unsupported.into()),
+        }
+    }
+
+    fn tcp(stream: TcpStream) -> io::Result<Self> {
+        stream.set_nonblocking(false)?;
+        stream.set_nodelay(true)?;
+        Ok(Self {
+            reader: BufReader::new(Box::new(stream.try_clone()?)),
+            writer: Box::new(stream),
+        })
+    }
+
+    #[cfg(unix)]
+    fn unix(stream: UnixStream) -> io::Result<Self> {
+        stream.set_nonblocking(false)?;
+        Ok(Self {
+            reader: BufReader::new(Box::new(stream.try_clone()?)),
+            writer: Box::new(stream),
+        })
+    }
+
+    pub fn send(&mut self, message: impl fmt::Display) -> io::Result<()> {
+        writeln!(self.writer, "{}", message)?;
+        self.writer.flush()
+    }
+
+    /// The next message, or `None` if the other end closed the connection.
+    pub fn receive<M: FromStr<Err = String>>(&mut self) -> io::Result<Option<M>> {
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        line.trim_end()
+            .parse()
+            .map(Some)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+pub enum Listener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(UnixListener, PathBuf),
+}
+
+impl Listener {
+    pub fn bind(address: &Address) -> io::Result<Self> {
+        match address {
+            Address::Tcp(addr) => Ok(Self::Tcp(TcpListener::bind(addr)?)),
+            #[cfg(unix)]
+            Address::Unix(path) => {
+                // A socket left behind by a server that was killed.
+                if path.exists() && UnixStream::connect(path).is_err() {
+                    std::fs::remove_file(path)?;
+                }
+                Ok(Self::Unix(UnixListener::bind(path)?, path.clone()))
+            }
+            #[cfg(not(unix))]
+            Address::Unix(_) => Err(io::ErrorKind::Unsupported.into()),
+        }
+    }
+
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        match self {
+            Self::Tcp(listener) => listener.set_nonblocking(nonblocking),
+            #[cfg(unix)]
+            Self::Unix(listener, _) => listener.set_nonblocking(nonblocking),
+        }
+    }
+
+    pub fn accept(&self) -> io::Result<Connection> {
+        match self {
+            Self::Tcp(listener) => Connection::tcp(listener.accept()?.0),
+            #[cfg(unix)]
+            Self::Unix(listener, _) => Connection::unix(listener.accept()?.0),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for Listener {
+    fn drop(&mut self) {
+        // Otherwise, binding to the same path fails next time.
+        if let Self::Unix(_, path) = self {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Address, ClientMessage, ServerMessage};
+    use crate::{to_word, Pattern};
+
+    #[test]
+    fn round_trip() {
+        for message in [
+            ServerMessage::Hello { version: 1 },
+            ServerMessage::Game { max_guesses: 6 },
+            ServerMessage::Pattern("-#+--".parse().unwrap()),
+            ServerMessage::Rejected("'xyzzy' is not in the word list".to_string()),
+            ServerMessage::Solved(3),
+            ServerMessage::Failed(to_word("right")),
+            ServerMessage::Bye,
+        ] {
+            assert_eq!(message.to_string().parse(), Ok(message));
+        }
+        for message in [
+            ClientMessage::Guess(to_word("tares")),
+            ClientMessage::Resign,
+        ] {
+            assert_eq!(message.to_string().parse(), Ok(message));
+        }
+        assert_eq!(
+            ServerMessage::Pattern(Pattern::SOLVED).to_string(),
+            "PATTERN #####"
+        );
+        assert!("GUESS toolong".parse::<ClientMessage>().is_err());
+    }
+
+    #[test]
+    fn addresses() {
+        assert!(matches!(
+            "127.0.0.1:7878".parse(),
+            Ok(Address::Tcp(addr)) if addr.port() == 7878
+        ));
+        assert!("[::1]:7878".parse::<Address>().is_ok());
+        assert!("0.0.0.0:7878".parse::<Address>().is_err());
+        #[cfg(unix)]
+        assert_eq!(
+            "/tmp/rogerthat.sock".parse(),
+            Ok(Address::Unix("/tmp/rogerthat.sock".into()))
+        );
+    }
+}
@@ -1,29 +1,130 @@
-use crate::{to_word, Guesser, Word, Wordle, GAMES};
+use crate::{
+    game::{GameRecord, Observer, Printer},
+    words::WordLists,
+    Guesser, Word, Wordle,
+};
 use colored::Colorize;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+};
 
+/// Plays the answers of the word lists, on `threads` worker threads (1 by default).
+/// Every worker has its own guesser made by `mk`,
+/// and the games are reported (and returned) in the order of the answers.
+/// With several threads, the guessers must not print anything themselves
+/// (see `Options::verbose`), or it would be mixed up with the reports.
 pub fn run_all<G: Guesser>(
-    mut mk: impl FnMut() -> G,
+    mk: impl Fn() -> G + Sync,
     num_rounds: Option<usize>,
     skipped_rounds: Option<usize>,
-) {
+    threads: Option<usize>,
+) -> Vec<GameRecord> {
     let w = Wordle::new();
-    let mut guesser = (mk)();
-    for answer in GAMES
-        .split_whitespace()
+    let answers: Vec<Word> = WordLists::global()
+        .answers
+        .iter()
         .skip(skipped_rounds.unwrap_or(0))
         .take(num_rounds.unwrap_or(10))
-    {
-        println!("{}", "New game".blue());
-
-        let answer_b: Word = to_word(answer);
-        if let Some(score) = w.play(&answer_b, &mut guesser) {
-            println!(
-                "The answer is '{}', took {} tries.",
-                answer.to_uppercase().blue(),
-                score.to_string().blue().bold()
-            );
-        } else {
-            eprintln!("failed to guess");
+        .copied()
+        .collect();
+    let threads = threads.unwrap_or(1).clamp(1, answers.len().max(1));
+
+    if threads == 1 {
+        let mut guesser = (mk)();
+        let mut records = Vec::new();
+        for answer in &answers {
+            println!("{}", "New game".blue());
+            let record = w.play_observed(answer, &mut guesser, &mut Printer);
+            report(&record);
+            records.push(record);
+        }
+        return records;
+    }
+
+    let next = AtomicUsize::new(0);
+    let (tx, rx) = mpsc::channel();
+    thread::scope(|s| {
+        for _ in 0..threads {
+            let tx = tx.clone();
+            let (w, mk, next, answers) = (&w, &mk, &next, &answers);
+            s.spawn(move || {
+                let mut guesser = (mk)();
+                // Take the answers one at a time, so that slow games don't hold up a worker's share.
+                while let Some(answer) = answers.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    let record = w.play(answer, &mut guesser);
+                    if tx.send(record).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(tx);
+
+        let mut reporter = Reporter::new(&answers);
+        for record in rx {
+            reporter.add(record);
+        }
+        reporter.into_records()
+    })
+}
+
+/// Reports every game as soon as all the games before it are done,
+/// so that games finishing out of order are still reported in the order of the answers.
+pub(crate) struct Reporter<'a> {
+    answers: &'a [Word],
+    done: HashMap<Word, GameRecord>,
+    /// The games reported so far.
+    reported: Vec<GameRecord>,
+}
+
+impl<'a> Reporter<'a> {
+    pub(crate) fn new(answers: &'a [Word]) -> Self {
+        Self {
+            answers,
+            done: HashMap::new(),
+            reported: Vec::new(),
         }
     }
+
+    pub(crate) fn add(&mut self, record: GameRecord) {
+        self.done.insert(record.answer, record);
+        while let Some(record) = self
+            .answers
+            .get(self.reported.len())
+            .and_then(|a| self.done.remove(a))
+        {
+            println!("{}", "New game".blue());
+            for turn in &record.turns {
+                Printer.turn(turn);
+            }
+            report(&record);
+            self.reported.push(record);
+        }
+    }
+
+    /// Whether every game was reported.
+    pub(crate) fn is_complete(&self) -> bool {
+        self.reported.len() == self.answers.len()
+    }
+
+    pub(crate) fn into_records(self) -> Vec<GameRecord> {
+        self.reported
+    }
+}
+
+fn report(record: &GameRecord) {
+    if let Some(score) = record.score() {
+        println!(
+            "The answer is '{}', took {} tries.",
+            record.answer.to_string().to_uppercase().blue(),
+            score.to_string().blue().bold()
+        );
+    } else {
+        eprintln!("failed to guess");
+    }
 }
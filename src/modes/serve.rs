@@ -0,0 +1,217 @@
+use super::protocol::{Address, ClientMessage, Connection, Listener, ServerMessage, VERSION};
+use super::run_all::Reporter;
+use crate::game::{Game, GameRecord, Outcome, Turn};
+use crate::{words::WordLists, Word};
+use std::{
+    collections::{HashSet, VecDeque},
+    io,
+    sync::Mutex,
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Hosts the answers of the word lists (all of them by default) for the clients
+/// connecting to `address`, until every game was played.
+/// The games of a client that leaves in the middle of a game are handed out again.
+pub fn serve(
+    address: &Address,
+    num_rounds: Option<usize>,
+    skipped_rounds: Option<usize>,
+    hard: bool,
+    max_guesses: usize,
+) -> io::Result<()> {
+    let dictionary: HashSet<Word> = WordLists::global().words().collect();
+    let answers: Vec<Word> = WordLists::global()
+        .answers
+        .iter()
+        .skip(skipped_rounds.unwrap_or(0))
+        .take(num_rounds.unwrap_or(usize::MAX))
+        .copied()
+        .collect();
+    let queue = Mutex::new(answers.iter().copied().collect::<VecDeque<_>>());
+    let reporter = Mutex::new(Reporter::new(&answers));
+
+    let listener = Listener::bind(address)?;
+    // Polled, so that the server stops once the last game is over.
+    listener.set_nonblocking(true)?;
+    println!("Serving {} games on {}", answers.len(), address);
+
+    thread::scope(|s| {
+        let (queue, reporter, dictionary) = (&queue, &reporter, &dictionary);
+        while !reporter.lock().unwrap().is_complete() {
+            match listener.accept() {
+                Ok(mut connection) => {
+                    s.spawn(move || {
+                        let result = host(
+                            &mut connection,
+                            queue,
+                            reporter,
+                            dictionary,
+                            hard,
+                            max_guesses,
+                        );
+                        if let Err(e) = result {
+                            eprintln!("Connection lost: {}", e);
+                        }
+                    });
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    thread::sleep(Duration::from_millis(50))
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Plays games with one client until there are no more.
+fn host(
+    connection: &mut Connection,
+    queue: &Mutex<VecDeque<Word>>,
+    reporter: &Mutex<Reporter>,
+    dictionary: &HashSet<Word>,
+    hard: bool,
+    max_guesses: usize,
+) -> io::Result<()> {
+    connection.send(ServerMessage::Hello { version: VERSION })?;
+    loop {
+        let Some(answer) = queue.lock().unwrap().pop_front() else {
+            return connection.send(ServerMessage::Bye);
+        };
+        let game = Game::new(answer, dictionary)
+            .with_hard_mode(hard)
+            .with_max_guesses(max_guesses);
+        match play(connection, game, answer, dictionary) {
+            Ok(record) => reporter.lock().unwrap().add(record),
+            Err(e) => {
+                queue.lock().unwrap().push_back(answer);
+                return Err(e);
+            }
+        }
+    }
+}
+
+fn play(
+    connection: &mut Connection,
+    mut game: Game,
+    answer: Word,
+    dictionary: &HashSet<Word>,
+) -> io::Result<GameRecord> {
+    connection.send(ServerMessage::Game {
+        max_guesses: game.guesses_left(),
+    })?;
+    let mut turns = Vec::new();
+    // Dictionary words that could still be the answer, to report on each turn.
+    let mut candidates: Vec<Word> = dictionary.iter().copied().collect();
+    // Resigning right after a rejected guess means the client can't come up with a valid one.
+    let mut rejection = None;
+
+    let outcome = loop {
+        let start = Instant::now();
+        let guess = match connection.receive() {
+            Ok(Some(ClientMessage::Guess(word))) => word,
+            Ok(Some(ClientMessage::Resign)) => {
+                break rejection.map_or(Outcome::Failed, Outcome::Invalid)
+            }
+            Ok(None) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "the client left in the middle of a game",
+                ))
+            }
+            Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+                connection.send(ServerMessage::Rejected(e.to_string()))?;
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        let elapsed = start.elapsed();
+
+        let pattern = match game.submit(guess) {
+            Ok(pattern) => {
+                rejection = None;
+                pattern
+            }
+            Err(e) => {
+                rejection = Some(e);
+                connection.send(ServerMessage::Rejected(e.to_string()))?;
+                continue;
+            }
+        };
+        let remaining_before = candidates.len();
+        let last = game.history().last().unwrap();
+        candidates.retain(|word| last.matches(word));
+        turns.push(Turn {
+            guess,
+            pattern,
+            remaining_before,
+            remaining_after: candidates.len(),
+            elapsed,
+        });
+
+        if let Some(outcome) = game.outcome() {
+            break outcome;
+        }
+        connection.send(ServerMessage::Pattern(pattern))?;
+    };
+
+    connection.send(match outcome {
+        Outcome::Solved => ServerMessage::Solved(turns.len()),
+        Outcome::Failed | Outcome::Invalid(_) => ServerMessage::Failed(answer),
+    })?;
+    Ok(GameRecord {
+        answer,
+        turns,
+        outcome,
+    })
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::play;
+    use crate::game::{Game, Outcome};
+    use crate::modes::protocol::{Address, ClientMessage, Connection, Listener, ServerMessage};
+    use crate::to_word;
+    use std::{collections::HashSet, thread};
+
+    #[test]
+    fn resign_after_recovering() {
+        let address = Address::Unix(
+            std::env::temp_dir().join(format!("rogerthat-test-{}.sock", std::process::id())),
+        );
+        let listener = Listener::bind(&address).unwrap();
+        let client = thread::spawn(move || {
+            let mut connection = Connection::connect(&address).unwrap();
+            for message in [
+                ClientMessage::Guess(to_word("wrong")),
+                ClientMessage::Guess(to_word("fight")),
+                ClientMessage::Guess(to_word("tares")),
+                ClientMessage::Resign,
+            ] {
+                connection.send(message).unwrap();
+            }
+            // Until the end of the game, not to leave in the middle of it.
+            while let Some(message) = connection.receive::<ServerMessage>().unwrap() {
+                if matches!(message, ServerMessage::Failed(_)) {
+                    break;
+                }
+            }
+        });
+
+        let dictionary: HashSet<_> = ["right", "fight", "tares"].map(to_word).into();
+        let answer = to_word("right");
+        let mut connection = listener.accept().unwrap();
+        let record = play(
+            &mut connection,
+            Game::new(answer, &dictionary),
+            answer,
+            &dictionary,
+        )
+        .unwrap();
+        client.join().unwrap();
+        // The rejected guess is long forgotten: the client just gave up.
+        assert_eq!(record.outcome, Outcome::Failed);
+        assert_eq!(record.turns.len(), 2);
+    }
+}
@@ -0,0 +1,75 @@
+use super::protocol::{Address, ClientMessage, Connection, ServerMessage, VERSION};
+use crate::{nice_print, Guess, Guesser};
+use colored::Colorize;
+use std::{io, thread};
+
+/// Plays the games hosted by the server at `address` on `threads` connections (1 by default),
+/// each with its own guesser made by `mk`.
+pub fn solve<G: Guesser>(
+    mk: impl Fn() -> G + Sync,
+    address: &Address,
+    threads: Option<usize>,
+) -> io::Result<()> {
+    thread::scope(|s| {
+        let handles: Vec<_> = (0..threads.unwrap_or(1).max(1))
+            .map(|_| s.spawn(|| play_games(&mut (mk)(), address)))
+            .collect();
+        handles.into_iter().try_for_each(|h| h.join().unwrap())
+    })
+}
+
+fn play_games<G: Guesser>(guesser: &mut G, address: &Address) -> io::Result<()> {
+    let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+
+    let mut connection = Connection::connect(address)?;
+    let mut history = Vec::new();
+    let mut last_guess = None;
+    loop {
+        let message = connection.receive()?.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "the server closed the connection",
+            )
+        })?;
+        match message {
+            ServerMessage::Hello { version } if version == VERSION => continue,
+            ServerMessage::Hello { version } => {
+                return Err(invalid(format!("unsupported protocol version {}", version)))
+            }
+            ServerMessage::Game { .. } => {
+                println!("{}", "New game".blue());
+                history.clear();
+            }
+            ServerMessage::Pattern(mask) => {
+                let word = last_guess.ok_or_else(|| invalid("pattern before any guess".into()))?;
+                println!("Guessed '{}', received pattern: {}", nice_print(word), mask);
+                history.push(Guess { word, mask });
+            }
+            ServerMessage::Rejected(reason) => {
+                // The guesser would come up with the same guess again.
+                eprintln!("The guess was rejected: {}", reason);
+                connection.send(ClientMessage::Resign)?;
+                continue;
+            }
+            ServerMessage::Solved(guesses) => {
+                if let Some(word) = last_guess {
+                    println!(
+                        "The answer is '{}', took {} tries.",
+                        word.to_string().to_uppercase().blue(),
+                        guesses.to_string().blue().bold()
+                    );
+                }
+                continue;
+            }
+            ServerMessage::Failed(answer) => {
+                eprintln!("failed to guess '{}'", answer);
+                continue;
+            }
+            ServerMessage::Bye => return Ok(()),
+        }
+
+        let guess = guesser.guess(&history);
+        last_guess = Some(guess);
+        connection.send(ClientMessage::Guess(guess))?;
+    }
+}
@@ -0,0 +1,444 @@
+use super::interactive::{explain, load, replay, save, Entry};
+use crate::algorithms::{Interactive, Suggestion};
+use crate::{Correctness, Guess, Guesser, Pattern, Word};
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute, queue,
+    style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor},
+    terminal,
+};
+use std::{
+    io::{self, stdout, Write},
+    path::Path,
+};
+
+/// Number of suggestions listed when none was asked for.
+const LIST_SIZE: usize = 10;
+
+const KEYBOARD: [&str; 3] = ["qwertyuiop", "asdfghjkl", "zxcvbnm"];
+
+/// The row being entered: the letters typed so far and the color of every tile.
+#[derive(Debug)]
+struct Row {
+    letters: Vec<u8>,
+    tiles: [Correctness; 5],
+    /// The tile whose color the arrows change.
+    cursor: usize,
+}
+
+impl Default for Row {
+    fn default() -> Self {
+        Self {
+            letters: Vec::new(),
+            tiles: [Correctness::Wrong; 5],
+            cursor: 0,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Action {
+    None,
+    Submit(Guess),
+    Undo,
+    Quit,
+}
+
+/// Gray, then yellow, then green, then gray again.
+fn cycle(tile: Correctness, forward: bool) -> Correctness {
+    match (tile, forward) {
+        (Correctness::Wrong, true) | (Correctness::Correct, false) => Correctness::Misplaced,
+        (Correctness::Misplaced, true) | (Correctness::Wrong, false) => Correctness::Correct,
+        (Correctness::Correct, true) | (Correctness::Misplaced, false) => Correctness::Wrong,
+    }
+}
+
+impl Row {
+    /// The word of the row: the suggestion until one is typed.
+    fn word(&self, suggestion: Word) -> Option<Word> {
+        match self.letters.len() {
+            0 => Some(suggestion),
+            5 => Word::from_bytes(self.letters[..].try_into().unwrap()).ok(),
+            _ => None,
+        }
+    }
+
+    fn key(&mut self, key: KeyEvent, suggestion: Word) -> Action {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => return Action::Quit,
+            KeyCode::Char('c') if ctrl => return Action::Quit,
+            KeyCode::Char('z') if ctrl => return Action::Undo,
+            KeyCode::Char(c @ '1'..='5') => {
+                self.cursor = c as usize - '1' as usize;
+                self.tiles[self.cursor] = cycle(self.tiles[self.cursor], true);
+            }
+            KeyCode::Char(c) if c.is_ascii_alphabetic() && !ctrl && self.letters.len() < 5 => {
+                self.cursor = self.letters.len();
+                self.letters.push(c.to_ascii_lowercase() as u8);
+            }
+            KeyCode::Backspace if !self.letters.is_empty() => {
+                self.letters.pop();
+                self.cursor = self.letters.len().saturating_sub(1);
+            }
+            KeyCode::Tab => {
+                self.letters = suggestion.as_bytes().to_vec();
+            }
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(4),
+            KeyCode::Up | KeyCode::Char(' ') => {
+                self.tiles[self.cursor] = cycle(self.tiles[self.cursor], true)
+            }
+            KeyCode::Down => self.tiles[self.cursor] = cycle(self.tiles[self.cursor], false),
+            KeyCode::Enter => {
+                if let Some(word) = self.word(suggestion) {
+                    let mask = Pattern::from(self.tiles);
+                    return Action::Submit(Guess { word, mask });
+                }
+            }
+            _ => {}
+        }
+        Action::None
+    }
+}
+
+/// The most any guess revealed about every letter, `None` for the letters not guessed yet.
+fn letter_status(history: &[Guess]) -> [Option<Correctness>; 26] {
+    let rank = |c: Correctness| match c {
+        Correctness::Wrong => 0,
+        Correctness::Misplaced => 1,
+        Correctness::Correct => 2,
+    };
+    let mut status = [None; 26];
+    for guess in history {
+        for (&letter, tile) in guess
+            .word
+            .as_bytes()
+            .iter()
+            .zip(guess.mask.to_correctness())
+        {
+            let known = &mut status[(letter - b'a') as usize];
+            if known.is_none_or(|k| rank(tile) > rank(k)) {
+                *known = Some(tile);
+            }
+        }
+    }
+    status
+}
+
+fn color(tile: Option<Correctness>) -> Color {
+    match tile {
+        Some(Correctness::Correct) => Color::DarkGreen,
+        Some(Correctness::Misplaced) => Color::DarkYellow,
+        Some(Correctness::Wrong) => Color::DarkGrey,
+        None => Color::Reset,
+    }
+}
+
+/// A letter on a colored tile.
+fn tile(out: &mut impl Write, letter: u8, tile: Option<Correctness>) -> io::Result<()> {
+    queue!(
+        out,
+        SetBackgroundColor(color(tile)),
+        SetForegroundColor(Color::White),
+        Print(format!(" {} ", letter.to_ascii_uppercase() as char)),
+        ResetColor,
+        Print(" ")
+    )
+}
+
+/// What is shown on the screen.
+struct Screen<'a> {
+    history: &'a [Guess],
+    row: &'a Row,
+    /// `None` while thinking.
+    suggestion: Option<Word>,
+    ranking: &'a [Suggestion],
+    remaining: usize,
+    message: &'a str,
+}
+
+impl<'a> Screen<'a> {
+    fn new(
+        history: &'a [Guess],
+        row: &'a Row,
+        guesser: &'a Interactive,
+        suggestion: Option<Word>,
+        message: &'a str,
+    ) -> Self {
+        Self {
+            history,
+            row,
+            suggestion,
+            // The ranking of the last suggestion is out of date while thinking.
+            ranking: if suggestion.is_some() {
+                guesser.ranking()
+            } else {
+                &[]
+            },
+            remaining: guesser.remaining_count(),
+            message,
+        }
+    }
+
+    fn draw(&self, out: &mut impl Write) -> io::Result<()> {
+        queue!(out, terminal::Clear(terminal::ClearType::All))?;
+        let mut y = 1;
+        queue!(
+            out,
+            cursor::MoveTo(2, y),
+            Print(format!("Words still possible: {}", self.remaining))
+        )?;
+        y += 2;
+
+        // The board on the left, the suggestions on the right.
+        let board_top = y;
+        for guess in self.history {
+            queue!(out, cursor::MoveTo(2, y))?;
+            for (&letter, c) in guess
+                .word
+                .as_bytes()
+                .iter()
+                .zip(guess.mask.to_correctness())
+            {
+                tile(out, letter, Some(c))?;
+            }
+            y += 1;
+        }
+        let shown = match self.suggestion {
+            Some(word) if self.row.letters.is_empty() => word.as_bytes().to_vec(),
+            _ => self.row.letters.clone(),
+        };
+        queue!(out, cursor::MoveTo(2, y))?;
+        for (i, &c) in self.row.tiles.iter().enumerate() {
+            match shown.get(i) {
+                Some(&letter) => tile(out, letter, Some(c))?,
+                None => tile(out, b' ', Some(c))?,
+            }
+        }
+        queue!(
+            out,
+            cursor::MoveTo(2 + 4 * self.row.cursor as u16, y + 1),
+            Print(" ^ ")
+        )?;
+        if self.row.letters.is_empty() && self.suggestion.is_some() {
+            queue!(out, cursor::MoveTo(23, y), Print("(suggestion)"))?;
+        }
+
+        let x = 40;
+        let mut s_y = board_top;
+        queue!(
+            out,
+            cursor::MoveTo(x, s_y),
+            Print("  #  Guess  Information  Remaining  Answer  Possible")
+        )?;
+        for (i, s) in self.ranking.iter().enumerate() {
+            s_y += 1;
+            queue!(
+                out,
+                cursor::MoveTo(x, s_y),
+                Print(format!(
+                    "{:>3}  {}  {:>6.3} bits  {:>9.2}  {:>5.2}%  {}",
+                    i + 1,
+                    s.word.to_string().to_uppercase(),
+                    s.information,
+                    s.expected_remaining,
+                    s.probability * 100.0,
+                    if s.possible { "yes" } else { "no" }
+                ))
+            )?;
+        }
+        y = y.max(s_y) + 3;
+
+        let status = letter_status(self.history);
+        for (indent, keys) in KEYBOARD.iter().enumerate() {
+            queue!(out, cursor::MoveTo(2 + 2 * indent as u16, y))?;
+            for &letter in keys.as_bytes() {
+                tile(out, letter, status[(letter - b'a') as usize])?;
+            }
+            y += 1;
+        }
+        y += 1;
+
+        for help in [
+            "Letters type the guess (Tab: the suggestion). Left/Right pick a tile,",
+            "Up/Down/Space or 1-5 change its color. Enter submits, Ctrl-Z takes back, Esc quits.",
+        ] {
+            queue!(out, cursor::MoveTo(2, y), Print(help))?;
+            y += 1;
+        }
+        for (i, line) in self.message.lines().enumerate() {
+            queue!(out, cursor::MoveTo(2, y + 1 + i as u16), Print(line))?;
+        }
+        out.flush()
+    }
+}
+
+/// Puts the terminal back as it was, however the interface ends.
+struct RawTerminal;
+
+impl RawTerminal {
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(Self)
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        let _ = execute!(stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Helps with a game played elsewhere in a full-screen interface, until Esc.
+/// Like in interactive mode, the `list_size` best guesses are listed (10 if 0)
+/// and the session is resumed from and kept in the `session` file.
+pub fn tui(
+    new_guesser: impl Fn() -> Interactive,
+    list_size: usize,
+    session: Option<&Path>,
+) -> io::Result<()> {
+    let new_guesser = || {
+        let mut guesser = new_guesser();
+        guesser.set_quiet(true);
+        guesser
+    };
+    let list_size = if list_size == 0 { LIST_SIZE } else { list_size };
+    let mut entries = match session.filter(|path| path.exists()) {
+        Some(path) => load(path)?,
+        None => Vec::new(),
+    };
+    let (mut guesser, mut history) = replay(&new_guesser, list_size, &entries);
+    let mut row = Row::default();
+    let mut message = String::new();
+
+    let _terminal = RawTerminal::enter()?;
+    let mut out = stdout();
+    loop {
+        Screen::new(&history, &row, &guesser, None, "Thinking...").draw(&mut out)?;
+        let suggestion = guesser.guess(&history);
+
+        let action = loop {
+            Screen::new(&history, &row, &guesser, Some(suggestion), &message).draw(&mut out)?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    match row.key(key, suggestion) {
+                        Action::None => {}
+                        action => break action,
+                    }
+                }
+            }
+        };
+        message.clear();
+
+        match action {
+            Action::Quit => return Ok(()),
+            Action::Submit(guess) => {
+                if let Err(contradiction) = guesser.validate(&history, &guess) {
+                    let label = |i: usize| match i {
+                        i if i < history.len() => format!("guess {}", i + 1),
+                        _ => "this guess".to_string(),
+                    };
+                    message = explain(&contradiction, &history, label).join("\n");
+                    continue;
+                }
+                row = Row::default();
+                let entry = Entry::Guess(guess);
+                entry.apply(&mut guesser, &mut history);
+                entries.push(entry);
+                if guess.mask.is_solved() {
+                    message = format!("Solved in {} guesses!", history.len());
+                }
+            }
+            Action::Undo => match entries.pop() {
+                Some(entry) => {
+                    message = format!("Took back: {}", entry);
+                    (guesser, history) = replay(&new_guesser, list_size, &entries);
+                }
+                None => message = "Nothing to take back.".to_string(),
+            },
+            Action::None => unreachable!(),
+        }
+        if let Some(path) = session {
+            if let Err(e) = save(path, &entries) {
+                message = format!("Could not save the session to {}: {}", path.display(), e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{letter_status, Action, Row};
+    use crate::{to_word, Correctness, Guess};
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    fn press(row: &mut Row, code: KeyCode) -> Action {
+        row.key(KeyEvent::new(code, KeyModifiers::NONE), to_word("tares"))
+    }
+
+    #[test]
+    fn entering_a_row() {
+        let mut row = Row::default();
+        for c in "clo".chars() {
+            press(&mut row, KeyCode::Char(c));
+        }
+        // Not a word yet.
+        assert_eq!(press(&mut row, KeyCode::Enter), Action::None);
+        for c in "utx".chars() {
+            press(&mut row, KeyCode::Char(c));
+        }
+        press(&mut row, KeyCode::Char('2'));
+        press(&mut row, KeyCode::Char('2'));
+        press(&mut row, KeyCode::Right);
+        press(&mut row, KeyCode::Up);
+        assert_eq!(
+            press(&mut row, KeyCode::Enter),
+            Action::Submit(Guess {
+                word: to_word("clout"),
+                mask: "-#+--".parse().unwrap(),
+            })
+        );
+
+        // Without a word, the suggestion is the guess.
+        row = Row::default();
+        press(&mut row, KeyCode::Char('5'));
+        assert_eq!(
+            press(&mut row, KeyCode::Enter),
+            Action::Submit(Guess {
+                word: to_word("tares"),
+                mask: "----+".parse().unwrap(),
+            })
+        );
+        assert_eq!(
+            row.key(
+                KeyEvent::new(KeyCode::Char('z'), KeyModifiers::CONTROL),
+                to_word("tares")
+            ),
+            Action::Undo
+        );
+        assert_eq!(press(&mut row, KeyCode::Esc), Action::Quit);
+    }
+
+    #[test]
+    fn keyboard() {
+        let history = [
+            Guess {
+                word: to_word("sassy"),
+                mask: "+-#--".parse().unwrap(),
+            },
+            Guess {
+                word: to_word("yeast"),
+                mask: "-----".parse().unwrap(),
+            },
+        ];
+        let status = letter_status(&history);
+        let of = |c: u8| status[(c - b'a') as usize];
+        assert_eq!(of(b's'), Some(Correctness::Correct));
+        assert_eq!(of(b'a'), Some(Correctness::Wrong));
+        assert_eq!(of(b'z'), None);
+    }
+}
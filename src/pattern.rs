@@ -0,0 +1,253 @@
+use crate::{Correctness, Word};
+use std::{fmt, str::FromStr};
+
+/// The correctness pattern received for a guess, base-3 encoded in a byte.
+///
+/// Tile `i` is the `i`-th least significant base-3 digit, holding
+/// `Correctness as u8`. The encoding is shared by the pattern matrix,
+/// the memo tables and the mask buckets of the solvers.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Pattern(u8);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParsePatternError {
+    /// The pattern does not have exactly 5 tiles.
+    WrongLength(usize),
+    /// A tile is neither one of `-#+` nor one of the emoji squares.
+    UnknownTile(char),
+}
+
+const POWERS: [u8; 5] = [1, 3, 9, 27, 81];
+
+impl Pattern {
+    /// Number of distinct patterns (3^5).
+    pub const COUNT: usize = 3 * 3 * 3 * 3 * 3;
+
+    /// All green: the guess was the answer.
+    pub const SOLVED: Self = Self(Self::COUNT as u8 - 1);
+
+    pub fn compute(answer: &Word, guess: &Word) -> Self {
+        Correctness::compute(answer, guess).into()
+    }
+
+    pub fn from_id(id: u8) -> Option<Self> {
+        ((id as usize) < Self::COUNT).then_some(Self(id))
+    }
+
+    pub fn id(self) -> u8 {
+        self.0
+    }
+
+    /// The id as an index into tables of `Pattern::COUNT` entries.
+    #[inline(always)]
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    pub fn get(self, i: usize) -> Correctness {
+        match self.0 / POWERS[i] % 3 {
+            0 => Correctness::Wrong,
+            1 => Correctness::Misplaced,
+            _ => Correctness::Correct,
+        }
+    }
+
+    pub fn to_correctness(self) -> [Correctness; 5] {
+        [0, 1, 2, 3, 4].map(|i| self.get(i))
+    }
+
+    pub fn is_solved(self) -> bool {
+        self == Self::SOLVED
+    }
+
+    /// Whether some guess and answer produce this pattern.
+    /// The only ones that don't have 4 greens and 1 yellow:
+    /// the yellow letter would have to be at the position of one of the greens.
+    pub fn is_feasible(self) -> bool {
+        let mask = self.to_correctness();
+        let greens = mask.iter().filter(|&&c| c == Correctness::Correct).count();
+        !(greens == 4 && mask.contains(&Correctness::Misplaced))
+    }
+
+    /// All `Pattern::COUNT` patterns, in order of their ids.
+    pub fn all() -> impl Iterator<Item = Self> {
+        (0..Self::COUNT as u8).map(Self)
+    }
+
+    /// Only the patterns that can actually be received.
+    pub fn feasible() -> impl Iterator<Item = Self> {
+        Self::all().filter(|p| p.is_feasible())
+    }
+
+    /// The `-#+` notation of interactive mode.
+    pub fn to_text(self) -> String {
+        self.to_correctness()
+            .iter()
+            .map(|c| match c {
+                Correctness::Wrong => '-',
+                Correctness::Misplaced => '+',
+                Correctness::Correct => '#',
+            })
+            .collect()
+    }
+
+    /// The emoji squares of the game.
+    pub fn to_emoji(self) -> String {
+        self.to_correctness()
+            .iter()
+            .map(|c| match c {
+                Correctness::Wrong => '⬛',
+                Correctness::Misplaced => '🟨',
+                Correctness::Correct => '🟩',
+            })
+            .collect()
+    }
+}
+
+impl From<[Correctness; 5]> for Pattern {
+    fn from(mask: [Correctness; 5]) -> Self {
+        Self(mask.iter().zip(POWERS).map(|(&c, p)| c as u8 * p).sum())
+    }
+}
+
+impl From<Pattern> for [Correctness; 5] {
+    fn from(pattern: Pattern) -> Self {
+        pattern.to_correctness()
+    }
+}
+
+impl FromStr for Pattern {
+    type Err = ParsePatternError;
+
+    /// Accepts both the `-#+` notation and emoji squares
+    /// (with either the dark or the light gray square).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut mask = [Correctness::Wrong; 5];
+        let mut len = 0;
+        for ch in s.chars() {
+            let c = match ch {
+                '-' | '⬛' | '⬜' => Correctness::Wrong,
+                '+' | '🟨' => Correctness::Misplaced,
+                '#' | '🟩' => Correctness::Correct,
+                _ => return Err(ParsePatternError::UnknownTile(ch)),
+            };
+            if len < 5 {
+                mask[len] = c;
+            }
+            len += 1;
+        }
+        if len != 5 {
+            return Err(ParsePatternError::WrongLength(len));
+        }
+        Ok(mask.into())
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_emoji())
+    }
+}
+
+impl fmt::Debug for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Pattern({})", self.to_text())
+    }
+}
+
+impl fmt::Display for ParsePatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WrongLength(len) => write!(f, "expected 5 tiles, got {}", len),
+            Self::UnknownTile(ch) => write!(f, "{:?} is not a tile", ch),
+        }
+    }
+}
+
+impl std::error::Error for ParsePatternError {}
+
+/// In the `-#+` notation, so that patterns can be keys of JSON objects.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Pattern {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_text())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Pattern {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ParsePatternError, Pattern};
+    use crate::{Correctness::*, Word};
+    use std::collections::HashSet;
+
+    #[test]
+    fn round_trip() {
+        for pattern in Pattern::all() {
+            assert_eq!(Pattern::from(pattern.to_correctness()), pattern);
+            assert_eq!(pattern.to_text().parse(), Ok(pattern));
+            assert_eq!(pattern.to_emoji().parse(), Ok(pattern));
+        }
+    }
+
+    #[test]
+    fn encoding() {
+        assert_eq!(Pattern::from([Wrong; 5]).id(), 0);
+        assert_eq!(
+            Pattern::from([Misplaced, Wrong, Wrong, Wrong, Wrong]).id(),
+            1
+        );
+        assert_eq!(
+            Pattern::from([Wrong, Wrong, Wrong, Wrong, Correct]).id(),
+            2 * 81
+        );
+        assert_eq!(Pattern::from([Correct; 5]), Pattern::SOLVED);
+        assert_eq!(Pattern::all().count(), Pattern::COUNT);
+    }
+
+    #[test]
+    fn notations() {
+        let pattern = Pattern::from([Correct, Misplaced, Wrong, Wrong, Correct]);
+        assert_eq!(pattern.to_text(), "#+--#");
+        assert_eq!(pattern.to_emoji(), "🟩🟨⬛⬛🟩");
+        assert_eq!("🟩🟨⬜⬜🟩".parse(), Ok(pattern));
+        assert_eq!(
+            "#+--".parse::<Pattern>(),
+            Err(ParsePatternError::WrongLength(4))
+        );
+        assert_eq!(
+            "#+-x#".parse::<Pattern>(),
+            Err(ParsePatternError::UnknownTile('x'))
+        );
+    }
+
+    #[test]
+    fn feasible() {
+        // Every pattern between two words over a 4-letter alphabet.
+        let words: Vec<Word> = (0..4usize.pow(5))
+            .map(|mut n| {
+                let mut bytes = [0u8; 5];
+                for b in &mut bytes {
+                    *b = b'a' + (n % 4) as u8;
+                    n /= 4;
+                }
+                Word::from_bytes(bytes).unwrap()
+            })
+            .collect();
+        let mut seen = HashSet::new();
+        for answer in &words {
+            for guess in &words {
+                seen.insert(Pattern::compute(answer, guess));
+            }
+        }
+        assert_eq!(seen, Pattern::feasible().collect());
+        assert_eq!(seen.len(), Pattern::COUNT - 5);
+    }
+}
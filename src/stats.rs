@@ -0,0 +1,287 @@
+use crate::game::{GameRecord, Outcome};
+use std::{
+    collections::BTreeMap,
+    fmt,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+    time::Duration,
+};
+
+/// How a solver did over a run of games.
+pub struct Stats<'a> {
+    records: &'a [GameRecord],
+    /// Games taking more guesses than this count as failures.
+    limit: usize,
+}
+
+/// Number of answers listed as the worst ones.
+const WORST: usize = 10;
+
+impl<'a> Stats<'a> {
+    pub fn new(records: &'a [GameRecord], limit: usize) -> Self {
+        Self { records, limit }
+    }
+
+    /// Whether the game was solved within the limit.
+    fn is_success(&self, record: &GameRecord) -> bool {
+        record.score().is_some_and(|score| score <= self.limit)
+    }
+
+    /// Mean number of guesses of the solved games.
+    pub fn mean(&self) -> Option<f64> {
+        let scores: Vec<usize> = self.records.iter().filter_map(GameRecord::score).collect();
+        (!scores.is_empty()).then(|| scores.iter().sum::<usize>() as f64 / scores.len() as f64)
+    }
+
+    /// Number of solved games by number of guesses.
+    pub fn histogram(&self) -> BTreeMap<usize, usize> {
+        let mut histogram = BTreeMap::new();
+        for score in self.records.iter().filter_map(GameRecord::score) {
+            *histogram.entry(score).or_insert(0) += 1;
+        }
+        histogram
+    }
+
+    /// The games that were not solved within the limit.
+    pub fn failures(&self) -> impl Iterator<Item = &'a GameRecord> + '_ {
+        self.records.iter().filter(|r| !self.is_success(r))
+    }
+
+    /// The `n` games that took the most guesses (unsolved ones first), slowest first for ties.
+    pub fn worst(&self, n: usize) -> Vec<&'a GameRecord> {
+        let mut worst: Vec<&GameRecord> = self.records.iter().collect();
+        worst.sort_by_key(|r| {
+            (
+                std::cmp::Reverse(r.score().map_or(usize::MAX, |s| s)),
+                std::cmp::Reverse(r.elapsed()),
+            )
+        });
+        worst.truncate(n);
+        worst
+    }
+
+    /// Total time the solver spent thinking.
+    pub fn elapsed(&self) -> Duration {
+        self.records.iter().map(GameRecord::elapsed).sum()
+    }
+
+    pub fn num_guesses(&self) -> usize {
+        self.records.iter().map(|r| r.turns.len()).sum()
+    }
+
+    /// One line per game: the answer, the outcome, the number of guesses,
+    /// the thinking time in milliseconds and the guesses.
+    pub fn write_csv(&self, w: &mut impl Write) -> io::Result<()> {
+        writeln!(w, "answer,outcome,guesses,elapsed_ms,sequence")?;
+        for record in self.records {
+            writeln!(
+                w,
+                "{},{},{},{:.3},{}",
+                record.answer,
+                outcome_name(record.outcome),
+                record.turns.len(),
+                record.elapsed().as_secs_f64() * 1000.0,
+                record
+                    .turns
+                    .iter()
+                    .map(|t| t.guess.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            )?;
+        }
+        Ok(())
+    }
+
+    /// The summary and every game.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        use crate::Word;
+        use serde_json::json;
+
+        let games: Vec<_> = self
+            .records
+            .iter()
+            .map(|r| {
+                json!({
+                    "answer": r.answer,
+                    "outcome": outcome_name(r.outcome),
+                    "guesses": r.turns.iter().map(|t| t.guess).collect::<Vec<Word>>(),
+                    "patterns": r.turns.iter().map(|t| t.pattern).collect::<Vec<_>>(),
+                    "elapsed_ms": r.elapsed().as_secs_f64() * 1000.0,
+                })
+            })
+            .collect();
+        let histogram: BTreeMap<String, usize> = self
+            .histogram()
+            .into_iter()
+            .map(|(guesses, games)| (guesses.to_string(), games))
+            .collect();
+        let failures: Vec<Word> = self.failures().map(|r| r.answer).collect();
+        json!({
+            "games": self.records.len(),
+            "mean": self.mean(),
+            "limit": self.limit,
+            "histogram": histogram,
+            "failures": failures,
+            "elapsed_ms": self.elapsed().as_secs_f64() * 1000.0,
+            "num_guesses": self.num_guesses(),
+            "records": games,
+        })
+        .to_string()
+    }
+
+    /// Writes the report as JSON or CSV, depending on the extension of `path` (`.csv` or any other).
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("csv") => self.write_csv(&mut out)?,
+            #[cfg(feature = "serde")]
+            _ => out.write_all(self.to_json().as_bytes())?,
+            #[cfg(not(feature = "serde"))]
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "JSON reports need the `serde` feature",
+                ))
+            }
+        }
+        out.flush()
+    }
+}
+
+fn outcome_name(outcome: Outcome) -> &'static str {
+    match outcome {
+        Outcome::Solved => "solved",
+        Outcome::Failed => "failed",
+        Outcome::Invalid(_) => "invalid",
+    }
+}
+
+impl fmt::Display for Stats<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let games = self.records.len();
+        writeln!(f, "Games: {}", games)?;
+        match self.mean() {
+            Some(mean) => writeln!(f, "Mean guesses (solved games): {:.4}", mean)?,
+            None => writeln!(f, "Mean guesses (solved games): -")?,
+        }
+
+        let histogram = self.histogram();
+        let most = histogram.values().copied().max().unwrap_or(0);
+        for (guesses, count) in &histogram {
+            // Bars of at most 40 characters.
+            let bar = "#".repeat((count * 40).div_ceil(most.max(1)));
+            writeln!(f, "{:>3} | {:<40} {}", guesses, bar, count)?;
+        }
+
+        let failures: Vec<&GameRecord> = self.failures().collect();
+        write!(
+            f,
+            "Failures (unsolved or over {} guesses): {}",
+            self.limit,
+            failures.len()
+        )?;
+        // The full list is in the JSON and CSV outputs.
+        for record in failures.iter().take(WORST) {
+            write!(f, " {}", record.answer)?;
+        }
+        if failures.len() > WORST {
+            write!(f, " …")?;
+        }
+        writeln!(f)?;
+
+        write!(f, "Worst answers:")?;
+        for record in self.worst(WORST) {
+            match record.score() {
+                Some(score) => write!(f, " {} ({})", record.answer, score)?,
+                None => write!(f, " {} (-)", record.answer)?,
+            }
+        }
+        writeln!(f)?;
+
+        let elapsed = self.elapsed();
+        write!(f, "Thinking time: {:.3}s", elapsed.as_secs_f64())?;
+        if let Some(per_guess) = elapsed.checked_div(self.num_guesses() as u32) {
+            write!(f, ", {:.3}ms per guess", per_guess.as_secs_f64() * 1000.0)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::{Stats, WORST};
+    use crate::game::{GameRecord, Outcome, Turn};
+    use crate::{to_word, Pattern};
+    use std::time::Duration;
+
+    /// The record of a game with these guesses, each thought over for 10ms.
+    pub(crate) fn record(answer: &str, guesses: &[&str], outcome: Outcome) -> GameRecord {
+        let answer = to_word(answer);
+        GameRecord {
+            answer,
+            turns: guesses
+                .iter()
+                .map(|&guess| Turn {
+                    guess: to_word(guess),
+                    pattern: Pattern::compute(&answer, &to_word(guess)),
+                    remaining_before: 0,
+                    remaining_after: 0,
+                    elapsed: Duration::from_millis(10),
+                })
+                .collect(),
+            outcome,
+        }
+    }
+
+    #[test]
+    fn report() {
+        let records = [
+            record("right", &["tares", "right"], Outcome::Solved),
+            record("fight", &["tares", "light", "fight"], Outcome::Solved),
+            record(
+                "tight",
+                &["tares", "light", "fight", "tight"],
+                Outcome::Solved,
+            ),
+            record("wight", &["tares", "light", "fight"], Outcome::Failed),
+        ];
+        let stats = Stats::new(&records, 3);
+        assert_eq!(stats.mean(), Some(3.0));
+        assert_eq!(
+            stats.histogram().into_iter().collect::<Vec<_>>(),
+            [(2, 1), (3, 1), (4, 1)]
+        );
+        let failures: Vec<_> = stats.failures().map(|r| r.answer).collect();
+        assert_eq!(failures, [to_word("tight"), to_word("wight")]);
+        assert_eq!(stats.worst(2)[0].answer, to_word("wight"));
+        assert_eq!(stats.worst(2)[1].answer, to_word("tight"));
+        assert_eq!(stats.elapsed(), Duration::from_millis(120));
+
+        let mut csv = Vec::new();
+        stats.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(csv.lines().count(), 5);
+        assert!(csv.contains("\nright,solved,2,20.000,tares right\n"));
+
+        #[cfg(feature = "serde")]
+        {
+            let json: serde_json::Value = serde_json::from_str(&stats.to_json()).unwrap();
+            assert_eq!(json["mean"], 3.0);
+            assert_eq!(json["histogram"]["3"], 1);
+            assert_eq!(json["records"][3]["outcome"], "failed");
+        }
+        assert!(stats
+            .to_string()
+            .contains("Failures (unsolved or over 3 guesses): 2 tight wight\n"));
+
+        // Only the first few failures in the report.
+        let failed = vec![records[3].clone(); WORST + 1];
+        let report = Stats::new(&failed, 3).to_string();
+        let line = report.lines().find(|l| l.starts_with("Failures")).unwrap();
+        assert_eq!(line.matches("wight").count(), WORST);
+        assert!(line.contains(&format!(": {} wight", WORST + 1)));
+        assert!(line.ends_with(" wight …"));
+    }
+}
@@ -0,0 +1,307 @@
+use crate::game::{GameRecord, Outcome};
+use crate::{Guess, Guesser, Pattern, Word, Wordle};
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Write as _},
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+const MAGIC: &[u8; 8] = b"RGTTREE1";
+
+/// What a solver guesses after every history it can come across:
+/// the first guess, and the subtree for every pattern it can receive
+/// (except the all-green one, which ends the game).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Tree {
+    pub guess: Word,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "BTreeMap::is_empty")
+    )]
+    pub children: BTreeMap<Pattern, Tree>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BuildError {
+    /// The guesser didn't find the answer.
+    Unsolved(GameRecord),
+    /// The guesser made different guesses after the same history,
+    /// so there is no single tree.
+    Inconsistent {
+        history: Vec<Guess>,
+        first: Word,
+        second: Word,
+    },
+}
+
+impl Tree {
+    /// Plays `guesser` on every one of `answers`, and puts all the games together.
+    pub fn build<G: Guesser>(guesser: &mut G, answers: &[Word]) -> Result<Self, BuildError> {
+        let w = Wordle::new();
+        let mut tree: Option<Tree> = None;
+        for answer in answers {
+            let record = w.play(answer, guesser);
+            if record.outcome != Outcome::Solved {
+                return Err(BuildError::Unsolved(record));
+            }
+            let first = record.turns[0].guess;
+            let mut node = tree.get_or_insert_with(|| Tree::leaf(first));
+            for (i, turn) in record.turns.iter().enumerate() {
+                if node.guess != turn.guess {
+                    return Err(BuildError::Inconsistent {
+                        history: record.turns[..i]
+                            .iter()
+                            .map(|t| Guess {
+                                word: t.guess,
+                                mask: t.pattern,
+                            })
+                            .collect(),
+                        first: node.guess,
+                        second: turn.guess,
+                    });
+                }
+                if let Some(next) = record.turns.get(i + 1) {
+                    node = node
+                        .children
+                        .entry(turn.pattern)
+                        .or_insert_with(|| Tree::leaf(next.guess));
+                }
+            }
+        }
+        Ok(tree.expect("There is at least one answer"))
+    }
+
+    fn leaf(guess: Word) -> Self {
+        Self {
+            guess,
+            children: BTreeMap::new(),
+        }
+    }
+
+    /// The subtree reached by `history`, if it follows the tree.
+    pub fn get(&self, history: &[Guess]) -> Option<&Tree> {
+        let mut node = self;
+        for guess in history {
+            if guess.word != node.guess {
+                return None;
+            }
+            node = node.children.get(&guess.mask)?;
+        }
+        Some(node)
+    }
+
+    /// Number of nodes, that is, of distinct histories.
+    pub fn num_nodes(&self) -> usize {
+        1 + self.children.values().map(Tree::num_nodes).sum::<usize>()
+    }
+
+    /// Writes the tree in depth-first order: every node is its guess,
+    /// the number of children, then every child as its pattern id followed by the node.
+    pub fn write_binary(&self, w: &mut impl Write) -> io::Result<()> {
+        w.write_all(MAGIC)?;
+        self.write_node(w)
+    }
+
+    fn write_node(&self, w: &mut impl Write) -> io::Result<()> {
+        w.write_all(self.guess.as_bytes())?;
+        // There are fewer patterns than 256.
+        w.write_all(&[self.children.len() as u8])?;
+        for (pattern, child) in &self.children {
+            w.write_all(&[pattern.id()])?;
+            child.write_node(w)?;
+        }
+        Ok(())
+    }
+
+    pub fn read_binary(r: &mut impl Read) -> io::Result<Self> {
+        let mut magic = [0; 8];
+        r.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid("not a strategy tree file"));
+        }
+        Self::read_node(r)
+    }
+
+    fn read_node(r: &mut impl Read) -> io::Result<Self> {
+        let mut guess = [0; 5];
+        r.read_exact(&mut guess)?;
+        let guess = Word::from_bytes(guess).map_err(|e| invalid(&e.to_string()))?;
+        let mut byte = [0; 1];
+        r.read_exact(&mut byte)?;
+        let mut node = Self::leaf(guess);
+        for _ in 0..byte[0] {
+            r.read_exact(&mut byte)?;
+            let pattern = Pattern::from_id(byte[0]).ok_or_else(|| invalid("invalid pattern"))?;
+            node.children.insert(pattern, Self::read_node(r)?);
+        }
+        Ok(node)
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Trees are always valid JSON")
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> io::Result<Self> {
+        serde_json::from_str(json).map_err(io::Error::from)
+    }
+
+    /// A Graphviz graph, with the guesses as nodes and the patterns as edges.
+    pub fn to_dot(&self) -> String {
+        let mut dot =
+            String::from("digraph strategy {\n    node [shape=box, fontname=monospace];\n");
+        self.write_dot(&mut dot, &mut 0);
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Writes the node as `n<id>` and its subtree with the next ids.
+    fn write_dot(&self, dot: &mut String, next_id: &mut usize) {
+        let id = *next_id;
+        *next_id += 1;
+        let _ = writeln!(
+            dot,
+            "    n{} [label=\"{}\"];",
+            id,
+            self.guess.to_string().to_uppercase()
+        );
+        for (pattern, child) in &self.children {
+            let _ = writeln!(
+                dot,
+                "    n{} -> n{} [label=\"{}\"];",
+                id,
+                *next_id,
+                pattern.to_text()
+            );
+            child.write_dot(dot, next_id);
+        }
+    }
+
+    /// Writes the tree in the format given by the extension of `path`:
+    /// `json`, `dot`, or the binary format for anything else.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        match path.extension().and_then(|e| e.to_str()) {
+            #[cfg(feature = "serde")]
+            Some("json") => out.write_all(self.to_json().as_bytes())?,
+            #[cfg(not(feature = "serde"))]
+            Some("json") => return Err(unsupported_json()),
+            Some("dot") => out.write_all(self.to_dot().as_bytes())?,
+            _ => self.write_binary(&mut out)?,
+        }
+        out.flush()
+    }
+
+    /// Reads a tree saved in the binary or the JSON format.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let mut bytes = Vec::new();
+        BufReader::new(File::open(path)?).read_to_end(&mut bytes)?;
+        if bytes.starts_with(MAGIC) {
+            return Self::read_binary(&mut bytes.as_slice());
+        }
+        #[cfg(feature = "serde")]
+        {
+            Self::from_json(std::str::from_utf8(&bytes).map_err(|e| invalid(&e.to_string()))?)
+        }
+        #[cfg(not(feature = "serde"))]
+        Err(unsupported_json())
+    }
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
+
+#[cfg(not(feature = "serde"))]
+fn unsupported_json() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "JSON trees need the `serde` feature",
+    )
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsolved(record) => write!(f, "'{}' was not solved", record.answer),
+            Self::Inconsistent {
+                history,
+                first,
+                second,
+            } => {
+                write!(f, "after")?;
+                if history.is_empty() {
+                    write!(f, " no guess")?;
+                }
+                for guess in history {
+                    write!(f, " {} {}", guess.word, guess.mask.to_text())?;
+                }
+                write!(
+                    f,
+                    ", the guess was '{}' in one game but '{}' in another",
+                    first, second
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+#[cfg(test)]
+mod tests {
+    use super::Tree;
+    use crate::{to_word, Guess, Guesser, Pattern, Word};
+
+    /// Guesses the first word of `words` that could still be the answer.
+    struct First(Vec<Word>);
+
+    impl Guesser for First {
+        fn guess(&mut self, history: &[Guess]) -> Word {
+            *self
+                .0
+                .iter()
+                .find(|w| history.iter().all(|g| g.matches(w)))
+                .unwrap()
+        }
+    }
+
+    fn tree() -> Tree {
+        let answers = ["right", "tight", "wight", "fight", "cigar"].map(to_word);
+        let mut guesser = First(["tares"].map(to_word).into_iter().chain(answers).collect());
+        Tree::build(&mut guesser, &answers).unwrap()
+    }
+
+    #[test]
+    fn build() {
+        let tree = tree();
+        assert_eq!(tree.guess, to_word("tares"));
+        // "wight" and "fight" share their pattern, the others have their own.
+        assert_eq!(tree.children.len(), 4);
+        let history = [Guess {
+            word: to_word("tares"),
+            mask: Pattern::compute(&to_word("fight"), &to_word("tares")),
+        }];
+        let node = tree.get(&history).unwrap();
+        assert_eq!(node.guess, to_word("wight"));
+        assert_eq!(node.children.len(), 1);
+        assert_eq!(tree.num_nodes(), 6);
+    }
+
+    #[test]
+    fn formats() {
+        let tree = tree();
+        let mut bytes = Vec::new();
+        tree.write_binary(&mut bytes).unwrap();
+        assert_eq!(Tree::read_binary(&mut bytes.as_slice()).unwrap(), tree);
+        #[cfg(feature = "serde")]
+        assert_eq!(Tree::from_json(&tree.to_json()).unwrap(), tree);
+        let dot = tree.to_dot();
+        assert!(dot.contains("n0 [label=\"TARES\"]"));
+        assert_eq!(dot.matches("->").count(), tree.num_nodes() - 1);
+    }
+}
@@ -0,0 +1,136 @@
+use std::{fmt, ops::Index, str::FromStr};
+
+/// A five-letter word, stored as its lowercase ASCII letters (5 bytes, `Copy`).
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Word([u8; 5]);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseWordError {
+    /// The word does not have exactly 5 characters.
+    WrongLength(usize),
+    /// The word contains something else than an ASCII letter.
+    NotALetter(char),
+}
+
+impl Word {
+    pub fn as_bytes(&self) -> &[u8; 5] {
+        &self.0
+    }
+
+    /// Builds a word from raw bytes, which must be lowercase ASCII letters.
+    pub fn from_bytes(bytes: [u8; 5]) -> Result<Self, ParseWordError> {
+        match bytes.iter().find(|b| !b.is_ascii_lowercase()) {
+            Some(&b) => Err(ParseWordError::NotALetter(b as char)),
+            None => Ok(Self(bytes)),
+        }
+    }
+}
+
+impl FromStr for Word {
+    type Err = ParseWordError;
+
+    /// Accepts 5 ASCII letters in any case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut word = [0u8; 5];
+        let mut len = 0;
+        for ch in s.chars() {
+            if !ch.is_ascii_alphabetic() {
+                return Err(ParseWordError::NotALetter(ch));
+            }
+            if len < 5 {
+                word[len] = ch.to_ascii_lowercase() as u8;
+            }
+            len += 1;
+        }
+        if len != 5 {
+            return Err(ParseWordError::WrongLength(len));
+        }
+        Ok(Self(word))
+    }
+}
+
+impl Index<usize> for Word {
+    type Output = u8;
+
+    #[inline(always)]
+    fn index(&self, i: usize) -> &u8 {
+        &self.0[i]
+    }
+}
+
+impl fmt::Display for Word {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Only ever contains ASCII letters.
+        f.write_str(std::str::from_utf8(&self.0).unwrap())
+    }
+}
+
+impl fmt::Debug for Word {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Word({:?})", self.to_string())
+    }
+}
+
+impl fmt::Display for ParseWordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WrongLength(len) => write!(f, "expected 5 letters, got {}", len),
+            Self::NotALetter(ch) => write!(f, "{:?} is not an ASCII letter", ch),
+        }
+    }
+}
+
+impl std::error::Error for ParseWordError {}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Word {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Word {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ParseWordError, Word};
+
+    #[test]
+    fn parse() {
+        let word: Word = "Tares".parse().unwrap();
+        assert_eq!(word.as_bytes(), b"tares");
+        assert_eq!(word.to_string(), "tares");
+        assert_eq!("tare".parse::<Word>(), Err(ParseWordError::WrongLength(4)));
+        assert_eq!(
+            "taress".parse::<Word>(),
+            Err(ParseWordError::WrongLength(6))
+        );
+        assert_eq!(
+            "tar3s".parse::<Word>(),
+            Err(ParseWordError::NotALetter('3'))
+        );
+        assert_eq!(
+            "tarés".parse::<Word>(),
+            Err(ParseWordError::NotALetter('é'))
+        );
+    }
+
+    #[test]
+    fn order() {
+        let mut words: Vec<Word> = ["right", "abcde", "wrong"]
+            .iter()
+            .map(|w| w.parse().unwrap())
+            .collect();
+        words.sort();
+        assert_eq!(
+            words.iter().map(Word::to_string).collect::<Vec<_>>(),
+            ["abcde", "right", "wrong"]
+        );
+    }
+}
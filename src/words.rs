@@ -0,0 +1,283 @@
+use crate::{ParseWordError, Word, DICTIONARY, GAMES};
+use once_cell::sync::OnceCell;
+use std::{collections::HashMap, fmt, io, path::Path};
+
+static GLOBAL: OnceCell<WordLists> = OnceCell::new();
+
+/// The allowed guesses with their frequencies, and the answers to play.
+pub struct WordLists {
+    /// Every allowed guess with its count in the corpus, in file order.
+    pub dictionary: Vec<(Word, usize)>,
+    /// The answers of the games to play, in file order.
+    pub answers: Vec<Word>,
+}
+
+#[derive(Debug)]
+pub struct LoadError {
+    /// The path of the file, or `<embedded>` for the lists built into the binary.
+    pub file: String,
+    /// 1-based line number, 0 if the error is not about a particular line.
+    pub line: usize,
+    pub kind: LoadErrorKind,
+}
+
+#[derive(Debug)]
+pub enum LoadErrorKind {
+    Io(io::Error),
+    /// A dictionary line is not of the format: word + space + frequency.
+    MissingCount,
+    BadWord(ParseWordError),
+    BadCount(String),
+    /// Something follows the word on a line of the answers file.
+    TrailingText(String),
+    /// The word already appeared on an earlier line.
+    Duplicate {
+        first_line: usize,
+    },
+    /// An answer that could never be guessed.
+    NotInDictionary(Word),
+    Empty,
+}
+
+const EMBEDDED: &str = "<embedded>";
+
+impl WordLists {
+    /// The lists built into the binary.
+    pub fn embedded() -> Self {
+        Self::parse(DICTIONARY, EMBEDDED, GAMES, EMBEDDED)
+            .expect("The embedded word lists are valid")
+    }
+
+    /// Reads the given files, falling back to the embedded list for any that is `None`.
+    ///
+    /// The dictionary has one word per line followed by a space and its frequency,
+    /// the answers file has one word per line. Blank lines are ignored.
+    pub fn load(dictionary: Option<&Path>, answers: Option<&Path>) -> Result<Self, LoadError> {
+        let read = |path: Option<&Path>, embedded: &'static str| match path {
+            Some(path) => std::fs::read_to_string(path)
+                .map(|text| (text, path.display().to_string()))
+                .map_err(|e| LoadError {
+                    file: path.display().to_string(),
+                    line: 0,
+                    kind: LoadErrorKind::Io(e),
+                }),
+            None => Ok((embedded.to_string(), EMBEDDED.to_string())),
+        };
+        let (dictionary, dictionary_file) = read(dictionary, DICTIONARY)?;
+        let (answers, answers_file) = read(answers, GAMES)?;
+        Self::parse(&dictionary, &dictionary_file, &answers, &answers_file)
+    }
+
+    /// Like `load`, but from the contents of the files.
+    /// The file names are only used for error messages.
+    pub fn parse(
+        dictionary: &str,
+        dictionary_file: &str,
+        answers: &str,
+        answers_file: &str,
+    ) -> Result<Self, LoadError> {
+        let mut seen = HashMap::new();
+        let mut parsed_dictionary = Vec::new();
+        for (line, word, count) in lines(dictionary) {
+            let error = |kind| LoadError {
+                file: dictionary_file.to_string(),
+                line,
+                kind,
+            };
+            let count = count.ok_or_else(|| error(LoadErrorKind::MissingCount))?;
+            let word = word.map_err(|e| error(LoadErrorKind::BadWord(e)))?;
+            let count = count
+                .parse()
+                .map_err(|_| error(LoadErrorKind::BadCount(count.to_string())))?;
+            if let Some(first_line) = seen.insert(word, line) {
+                return Err(error(LoadErrorKind::Duplicate { first_line }));
+            }
+            parsed_dictionary.push((word, count));
+        }
+        if parsed_dictionary.is_empty() {
+            return Err(LoadError {
+                file: dictionary_file.to_string(),
+                line: 0,
+                kind: LoadErrorKind::Empty,
+            });
+        }
+
+        let mut seen_answers = HashMap::new();
+        let mut parsed_answers = Vec::new();
+        for (line, word, rest) in lines(answers) {
+            let error = |kind| LoadError {
+                file: answers_file.to_string(),
+                line,
+                kind,
+            };
+            if let Some(rest) = rest {
+                return Err(error(LoadErrorKind::TrailingText(rest.to_string())));
+            }
+            let word = word.map_err(|e| error(LoadErrorKind::BadWord(e)))?;
+            if let Some(first_line) = seen_answers.insert(word, line) {
+                return Err(error(LoadErrorKind::Duplicate { first_line }));
+            }
+            if !seen.contains_key(&word) {
+                return Err(error(LoadErrorKind::NotInDictionary(word)));
+            }
+            parsed_answers.push(word);
+        }
+        if parsed_answers.is_empty() {
+            return Err(LoadError {
+                file: answers_file.to_string(),
+                line: 0,
+                kind: LoadErrorKind::Empty,
+            });
+        }
+
+        Ok(Self {
+            dictionary: parsed_dictionary,
+            answers: parsed_answers,
+        })
+    }
+
+    /// The lists used by the solvers and the game.
+    /// These are the embedded ones unless `install` was called before.
+    pub fn global() -> &'static Self {
+        GLOBAL.get_or_init(Self::embedded)
+    }
+
+    /// Makes these the lists returned by `global`.
+    /// Fails (giving them back) if `global` was already used.
+    pub fn install(self) -> Result<&'static Self, Self> {
+        GLOBAL.set(self)?;
+        Ok(GLOBAL.get().unwrap())
+    }
+
+    pub fn words(&self) -> impl Iterator<Item = Word> + '_ {
+        self.dictionary.iter().map(|&(word, _)| word)
+    }
+}
+
+/// Non-blank lines with their 1-based number, split into the word and the rest.
+fn lines(text: &str) -> impl Iterator<Item = (usize, Result<Word, ParseWordError>, Option<&str>)> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            let line = line.trim();
+            let (word, rest) = match line.split_once(char::is_whitespace) {
+                Some((word, rest)) => (word, Some(rest.trim())),
+                None => (line, None),
+            };
+            (i + 1, word.parse(), rest)
+        })
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}: {}", self.file, self.kind)
+        } else {
+            write!(f, "{}:{}: {}", self.file, self.line, self.kind)
+        }
+    }
+}
+
+impl fmt::Display for LoadErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{}", e),
+            Self::MissingCount => write!(f, "expected a word, a space and a frequency"),
+            Self::BadWord(e) => write!(f, "invalid word: {}", e),
+            Self::BadCount(count) => write!(f, "invalid frequency {:?}", count),
+            Self::TrailingText(text) => write!(f, "unexpected {:?} after the word", text),
+            Self::Duplicate { first_line } => {
+                write!(f, "duplicate of the word on line {}", first_line)
+            }
+            Self::NotInDictionary(word) => write!(f, "'{}' is not in the dictionary", word),
+            Self::Empty => write!(f, "no words"),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            LoadErrorKind::Io(e) => Some(e),
+            LoadErrorKind::BadWord(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LoadErrorKind, WordLists};
+    use crate::{to_word, ParseWordError};
+
+    fn error(dictionary: &str, answers: &str) -> (String, usize, LoadErrorKind) {
+        let e = WordLists::parse(dictionary, "dict", answers, "answers")
+            .err()
+            .unwrap();
+        (e.file, e.line, e.kind)
+    }
+
+    #[test]
+    fn embedded() {
+        let lists = WordLists::embedded();
+        assert_eq!(lists.dictionary.len(), 12947);
+        assert_eq!(lists.answers.len(), 2309);
+        assert_eq!(lists.answers[0], to_word("cigar"));
+    }
+
+    #[test]
+    fn valid() {
+        let lists =
+            WordLists::parse("right 10\n\nwrong 3\n", "dict", "right\n", "answers").unwrap();
+        assert_eq!(
+            lists.dictionary,
+            vec![(to_word("right"), 10), (to_word("wrong"), 3)]
+        );
+        assert_eq!(lists.answers, vec![to_word("right")]);
+    }
+
+    #[test]
+    fn line_numbers() {
+        assert!(matches!(
+            error("right 10\nwrung\n", "right"),
+            (f, 2, LoadErrorKind::MissingCount) if f == "dict"
+        ));
+        assert!(matches!(
+            error("right 10\n\nwrongs 3\n", "right"),
+            (_, 3, LoadErrorKind::BadWord(ParseWordError::WrongLength(6)))
+        ));
+        assert!(matches!(
+            error("right 10\nwröng 3\n", "right"),
+            (
+                _,
+                2,
+                LoadErrorKind::BadWord(ParseWordError::NotALetter('ö'))
+            )
+        ));
+        assert!(matches!(
+            error("right ten\n", "right"),
+            (_, 1, LoadErrorKind::BadCount(_))
+        ));
+        assert!(matches!(
+            error("right 10\nwrong 3\nright 2\n", "right"),
+            (_, 3, LoadErrorKind::Duplicate { first_line: 1 })
+        ));
+        assert!(matches!(
+            error("right 10\n", "right\nright\n"),
+            (f, 2, LoadErrorKind::Duplicate { first_line: 1 }) if f == "answers"
+        ));
+        assert!(matches!(
+            error("right 10\n", "right 10\n"),
+            (_, 1, LoadErrorKind::TrailingText(_))
+        ));
+        assert!(matches!(
+            error("right 10\n", "right\nwrong\n"),
+            (_, 2, LoadErrorKind::NotInDictionary(_))
+        ));
+        assert!(matches!(
+            error("right 10\n", "\n"),
+            (_, 0, LoadErrorKind::Empty)
+        ));
+    }
+}